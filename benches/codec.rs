@@ -0,0 +1,74 @@
+//! Benchmark of the encoders and the decoder with a payload that contains every data type. Run it with
+//! `cargo bench --bench codec`.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use cayenne_lpp::*;
+
+const ITERATIONS: u32 = 1_000_000;
+
+fn encode(buffer: &mut [u8]) -> usize {
+    let mut lpp = CayenneLPP::new(buffer);
+
+    lpp.add_digital_input(1, black_box(0x55)).unwrap();
+    lpp.add_digital_output(2, black_box(0xAA)).unwrap();
+    lpp.add_analog_input(3, black_box(-12.34)).unwrap();
+    lpp.add_analog_output(4, black_box(56.78)).unwrap();
+    lpp.add_generic_sensor(5, black_box(123_456)).unwrap();
+    lpp.add_luminosity(6, black_box(500)).unwrap();
+    lpp.add_presence(7, black_box(1)).unwrap();
+    lpp.add_temperature(8, black_box(21.5)).unwrap();
+    lpp.add_relative_humidity(9, black_box(40.5)).unwrap();
+    lpp.add_accelerometer(10, black_box(0.12), black_box(-0.98), black_box(9.81)).unwrap();
+    lpp.add_barometric_pressure(11, black_box(1013.2)).unwrap();
+    lpp.add_voltage(12, black_box(3.3)).unwrap();
+    lpp.add_current(13, black_box(0.125)).unwrap();
+    lpp.add_frequency(14, black_box(868_100_000)).unwrap();
+    lpp.add_percentage(15, black_box(42)).unwrap();
+    lpp.add_altitude(16, black_box(-12)).unwrap();
+    lpp.add_concentration(17, black_box(415)).unwrap();
+    lpp.add_power(18, black_box(1500)).unwrap();
+    lpp.add_distance(19, black_box(1234)).unwrap();
+    lpp.add_energy(20, black_box(98_765)).unwrap();
+    lpp.add_direction(21, black_box(270)).unwrap();
+    lpp.add_unixtime(22, black_box(1_700_000_000)).unwrap();
+    lpp.add_gyrometer(23, black_box(1.5), black_box(2.5), black_box(3.5)).unwrap();
+    lpp.add_gps(24, black_box(42.3519), black_box(-87.9094), black_box(10.0)).unwrap();
+    lpp.add_switch(25, black_box(true)).unwrap();
+    lpp.add_concentration(26, black_box(800)).unwrap();
+    lpp.add_color(27, black_box(255), black_box(128), black_box(0)).unwrap();
+
+    lpp.payload_slice().len()
+}
+
+fn decode(payload: &[u8]) -> usize {
+    CayenneLPPDecoder::new(payload).iter().filter(|scalar| scalar.is_ok()).count()
+}
+
+fn decode_raw(payload: &[u8]) -> usize {
+    CayenneLPPDecoder::new(payload).raw_iter().filter(|scalar| scalar.is_ok()).count()
+}
+
+fn measure(name: &str, mut run: impl FnMut() -> usize) {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(run());
+    }
+    let elapsed = start.elapsed();
+
+    println!("{name}: {:.1} ns per payload", elapsed.as_nanos() as f64 / f64::from(ITERATIONS));
+}
+
+fn main() {
+    let mut buffer = [0u8; 256];
+    let size = encode(&mut buffer);
+    let payload = &buffer[..size];
+    assert_eq!(27, decode(payload));
+    assert_eq!(27, decode_raw(payload));
+
+    let mut encode_buffer = [0u8; 256];
+    measure("encode", || encode(black_box(&mut encode_buffer)));
+    measure("decode", || decode(black_box(payload)));
+    measure("decode raw", || decode_raw(black_box(payload)));
+}
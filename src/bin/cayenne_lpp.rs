@@ -0,0 +1,273 @@
+//! Command-line tool to decode and encode Cayenne LPP payloads, e.g., frames that are copied from the console of a
+//! network server. Build it with `cargo build --features cli`.
+
+use std::io::Read;
+use std::process::ExitCode;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use cayenne_lpp::*;
+use serde_json::{Map, Value};
+
+const USAGE: &str = "\
+Usage:
+    cayenne-lpp decode [--hex | --base64 | --raw] [--json] [PAYLOAD]
+    cayenne-lpp encode [--base64] TYPE:CHANNEL=VALUE[,VALUE...]...
+
+decode: Decodes a payload in hex or base64 and prints the channel, type, value and unit of every entry as a table
+        or, with --json, as JSON. The payload is read from stdin if it is omitted. Its format is detected
+        automatically unless --hex or --base64 is given, --raw reads the binary payload from stdin. A payload
+        that is valid in both formats (e.g., ABCD) is decoded as hex, so base64 payloads that only consist of
+        hex digits need --base64.
+
+encode: Encodes the values into a payload and prints it in hex or, with --base64, in base64. TYPE is the name of
+        a data type, e.g., temperature, relative_humidity or digital_output. Data types with several values
+        take them in the order of their fields, e.g., gps:1=42.3519,-87.9094,10 for latitude, longitude and
+        altitude.";
+
+/// Format of the payload that is decoded.
+enum Input {
+    Auto,
+    Hex,
+    Base64,
+    Raw,
+}
+
+/// Parses a payload in hex, e.g., `03 67 01 10` or `0x03670110`.
+fn parse_hex(text: &str) -> Option<Vec<u8>> {
+    let digits: Vec<u8> = text.bytes().filter(|byte| !byte.is_ascii_whitespace()).collect();
+    let digits = digits.strip_prefix(b"0x").unwrap_or(&digits);
+    if digits.is_empty() || !digits.len().is_multiple_of(2) || !digits.iter().all(u8::is_ascii_hexdigit) {
+        return None
+    }
+
+    digits.chunks(2).map(|pair| u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok()).collect()
+}
+
+/// Parses a payload in base64, e.g., `A2cBEA==`.
+fn parse_base64(text: &str) -> Option<Vec<u8>> {
+    let text: String = text.chars().filter(|char| !char.is_ascii_whitespace()).collect();
+    STANDARD.decode(text).ok()
+}
+
+/// Reads the payload from the argument or from stdin.
+fn read_payload(input: Input, payload: Option<&str>) -> Result<Vec<u8>, String> {
+    let read_error = |error: std::io::Error| format!("failed to read the payload from stdin: {}", error);
+
+    if let Input::Raw = input {
+        if payload.is_some() {
+            return Err("--raw reads the payload from stdin".into())
+        }
+
+        let mut bytes = Vec::new();
+        std::io::stdin().read_to_end(&mut bytes).map_err(read_error)?;
+        return Ok(bytes)
+    }
+
+    let text = match payload {
+        Some(payload) => payload.to_string(),
+        None => {
+            let mut text = String::new();
+            std::io::stdin().read_to_string(&mut text).map_err(read_error)?;
+            text
+        }
+    };
+
+    let bytes = match input {
+        Input::Hex => parse_hex(&text),
+        Input::Base64 => parse_base64(&text),
+        _ => parse_hex(&text).or_else(|| parse_base64(&text)),
+    };
+
+    bytes.ok_or_else(|| "the payload is neither valid hex nor valid base64".into())
+}
+
+/// Returns the number of decimal places that are needed to write every step of the resolution of a field, e.g., 1
+/// for the 0.5 % steps of a relative humidity.
+fn decimals(field: &LppField) -> usize {
+    let factor = f64::from(field.factor);
+    (0..9).find(|decimals| (10f64.powi(*decimals) / factor).fract() == 0.0).unwrap_or(9) as usize
+}
+
+/// Formats the value of a field with the number of decimal places of its resolution.
+fn field_text(value: f64, field: &LppField) -> String {
+    format!("{:.*}", decimals(field), value)
+}
+
+/// Converts the value of a field into a JSON number with the number of decimal places of its resolution. Fields
+/// without a fractional resolution are written as integers.
+fn field_json(value: f64, field: &LppField) -> Value {
+    let text = field_text(value, field);
+    match text.parse::<i64>() {
+        Ok(integer) => Value::from(integer),
+        Err(_) => text.parse::<f64>().map_or(Value::Null, Value::from),
+    }
+}
+
+/// Returns the unit of a data type, or the units of all of its fields if they differ (e.g., `°, °, m` for GPS).
+fn unit(fields: &[LppField]) -> String {
+    if fields.iter().all(|field| field.unit == fields[0].unit) {
+        return fields[0].unit.to_string()
+    }
+
+    fields.iter().map(|field| field.unit).collect::<Vec<_>>().join(", ")
+}
+
+fn decode(args: &[String]) -> Result<(), String> {
+    let mut input = Input::Auto;
+    let mut json = false;
+    let mut payload = None;
+
+    for arg in args {
+        match arg.as_str() {
+            "--hex" => input = Input::Hex,
+            "--base64" => input = Input::Base64,
+            "--raw" => input = Input::Raw,
+            "--json" => json = true,
+            _ if arg.starts_with("--") || payload.is_some() => return Err(USAGE.into()),
+            _ => payload = Some(arg.as_str()),
+        }
+    }
+
+    let bytes = read_payload(input, payload)?;
+    let mut rows = Vec::new();
+    let mut errors = 0;
+
+    for scalar in CayenneLPPDecoder::new(&bytes).iter() {
+        let scalar = match scalar {
+            Ok(scalar) => scalar,
+            Err(error) => {
+                eprintln!("error: {}", error);
+                errors += 1;
+                continue
+            }
+        };
+
+        // custom types are not decoded without a registry, so every value has a data type
+        let Some(lpp_type) = scalar.value.kind() else { continue };
+        let fields = lpp_type.info().fields;
+        let values = (0..fields.len()).filter_map(|index| scalar.value.field_value(index));
+        rows.push((scalar.channel, lpp_type, values.collect::<Vec<_>>(), unit(fields)));
+    }
+
+    if json {
+        let entries: Vec<Value> = rows.into_iter().map(|(channel, lpp_type, values, unit)| {
+            let fields = lpp_type.info().fields;
+            let value = match fields {
+                [field] => field_json(values[0], field),
+                _ => Value::Object(fields.iter().zip(values)
+                    .map(|(field, value)| (field.name.to_string(), field_json(value, field)))
+                    .collect::<Map<_, _>>()),
+            };
+
+            serde_json::json!({ "channel": channel, "type": lpp_type.name(), "value": value, "unit": unit })
+        }).collect();
+
+        println!("{}", serde_json::to_string_pretty(&entries).map_err(|error| error.to_string())?);
+    } else {
+        let rows: Vec<_> = rows.into_iter().map(|(channel, lpp_type, values, unit)| {
+            let fields = lpp_type.info().fields;
+            let value = match fields {
+                [field] => field_text(values[0], field),
+                _ => fields.iter().zip(values)
+                    .map(|(field, value)| format!("{}={}", field.name, field_text(value, field)))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            };
+
+            (channel.to_string(), lpp_type.name(), value, unit)
+        }).collect();
+
+        let width = |column: fn(&(String, &str, String, String)) -> usize, title: &str| {
+            rows.iter().map(column).chain([title.chars().count()]).max().unwrap_or(0)
+        };
+        let widths = [
+            width(|row| row.0.chars().count(), "channel"),
+            width(|row| row.1.chars().count(), "type"),
+            width(|row| row.2.chars().count(), "value"),
+        ];
+
+        println!("{:>3$}  {:<4$}  {:<5$}  unit", "channel", "type", "value", widths[0], widths[1], widths[2]);
+        for (channel, name, value, unit) in &rows {
+            let line = format!("{:>4$}  {:<5$}  {:<6$}  {}", channel, name, value, unit, widths[0], widths[1], widths[2]);
+            println!("{}", line.trim_end());
+        }
+    }
+
+    match errors {
+        0 => Ok(()),
+        _ => Err(format!("{} of the entries could not be decoded", errors)),
+    }
+}
+
+/// Parses a value of the command line, e.g., `temperature:5=21.5`.
+fn parse_scalar(arg: &str) -> Result<CayenneLPPScalar, String> {
+    let invalid = |reason: &str| format!("invalid value {}: {}", arg, reason);
+
+    let (key, values) = arg.split_once('=').ok_or_else(|| invalid("expected TYPE:CHANNEL=VALUE"))?;
+    let (name, channel) = key.split_once(':').ok_or_else(|| invalid("expected TYPE:CHANNEL=VALUE"))?;
+
+    let lpp_type = LppType::ALL.iter().copied().find(|lpp_type| lpp_type.name() == name)
+        .ok_or_else(|| invalid("unknown data type"))?;
+    let channel = channel.parse().map_err(|_| invalid("the channel is no number from 0 to 255"))?;
+    let values = values.split(',').map(|value| value.trim().parse()).collect::<Result<Vec<f64>, _>>()
+        .map_err(|_| invalid("the value is no number"))?;
+
+    let fields = lpp_type.info().fields;
+    let value = CayenneLPPValue::from_field_values(lpp_type, &values).ok_or_else(|| {
+        let names: Vec<_> = fields.iter().map(|field| field.name).collect();
+        invalid(&format!("expected the values {} with the types of the data type", names.join(",")))
+    })?;
+
+    Ok(CayenneLPPScalar { channel, value })
+}
+
+fn encode(args: &[String]) -> Result<(), String> {
+    let mut base64 = false;
+    let mut lpp = CayenneLPPVec::default();
+
+    for arg in args {
+        if arg == "--base64" {
+            base64 = true;
+            continue
+        }
+
+        let scalar = parse_scalar(arg)?;
+        lpp.add_scalar(&scalar).map_err(|error| format!("invalid value {}: {}", arg, error))?;
+    }
+
+    let payload = lpp.payload_slice();
+    if payload.is_empty() {
+        return Err(USAGE.into())
+    }
+
+    if base64 {
+        println!("{}", STANDARD.encode(payload));
+    } else {
+        println!("{}", payload.iter().map(|byte| format!("{:02X}", byte)).collect::<String>());
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let result = match args.split_first() {
+        Some((command, args)) if command == "decode" => decode(args),
+        Some((command, args)) if command == "encode" => encode(args),
+        Some((command, _)) if command == "help" || command == "--help" || command == "-h" => {
+            println!("{}", USAGE);
+            Ok(())
+        },
+        _ => Err(USAGE.into()),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use crate::cayenne_lpp_scalar::CayenneLPPScalar;
+use crate::cayenne_lpp_storage::CayenneLPPStorage;
+use crate::error::Error;
+use crate::{CayenneLPP, CayenneLPPCheckpoint, Encoded};
+
+/// Chainable builder that adds values to a [`CayenneLPP`] data structure, so a frame can be composed in a single
+/// expression. The builder is created with [`CayenneLPP::builder`]. It keeps the first error that occurs and skips all
+/// values that are added afterwards. `finish()` returns either the payload and how its values have been encoded, or
+/// this error.
+///
+/// ```
+/// use cayenne_lpp::{CayenneLPP, Encoded};
+///
+/// let mut buffer = [0u8; 64];
+/// let mut lpp = CayenneLPP::new(&mut buffer);
+///
+/// let (payload, encoded) = lpp.builder()
+///     .temperature(1, 21.5)
+///     .relative_humidity(2, 40.0)
+///     .gps(3, 42.3519, -87.9094, 10.0)
+///     .finish()
+///     .unwrap();
+/// assert_eq!(18, payload.len());
+/// assert_eq!(Encoded::Exact, encoded);
+/// ```
+#[must_use = "call finish() to commit the values"]
+pub struct CayenneLPPBuilder<'b, 'a, S> {
+    lpp: &'b mut CayenneLPP<'a, S>,
+    checkpoint: CayenneLPPCheckpoint,
+    encoded: Encoded,
+    error: Option<Error>
+}
+
+impl<'a, S: CayenneLPPStorage> CayenneLPP<'a, S> {
+    /// Creates a builder that appends values to the payload of the data structure.
+    pub fn builder(&mut self) -> CayenneLPPBuilder<'_, 'a, S> {
+        CayenneLPPBuilder {
+            checkpoint: self.checkpoint(),
+            lpp: self,
+            encoded: Encoded::Exact,
+            error: None
+        }
+    }
+}
+
+impl<'b, 'a, S: CayenneLPPStorage> CayenneLPPBuilder<'b, 'a, S> {
+    /// Adds a value with the given function, unless an error has already occurred.
+    fn add(mut self, add: impl FnOnce(&mut CayenneLPP<'a, S>) -> Result<Encoded, Error>) -> Self {
+        if self.error.is_none() {
+            match add(self.lpp) {
+                Ok(encoded) => self.encoded = self.encoded.and(encoded),
+                Err(error) => self.error = Some(error),
+            }
+        }
+
+        self
+    }
+
+    /// Returns the payload of the data structure, or the first error that occurred while adding the values. In case
+    /// of an error, all values that were added by the builder are removed again (see [`CayenneLPP::rollback`]).
+    /// The payload is returned together with `Encoded::Exact` if all values have been written as given, otherwise
+    /// with the result of the first value that has been clamped or wrapped.
+    pub fn finish(self) -> Result<(&'b [u8], Encoded), Error> {
+        match self.error {
+            Some(error) => {
+                self.lpp.rollback(self.checkpoint);
+                Err(error)
+            }
+            None => Ok((self.lpp.payload_slice(), self.encoded))
+        }
+    }
+
+    /// Adds a scalar value (see [`CayenneLPP::add_scalar`]).
+    pub fn scalar(self, scalar: &CayenneLPPScalar) -> Self {
+        self.add(|lpp| lpp.add_scalar(scalar))
+    }
+
+    /// Adds a digital input (see [`CayenneLPP::add_digital_input`]).
+    pub fn digital_input(self, channel: u8, value: u8) -> Self {
+        self.add(|lpp| lpp.add_digital_input(channel, value))
+    }
+
+    /// Adds a digital output (see [`CayenneLPP::add_digital_output`]).
+    pub fn digital_output(self, channel: u8, value: u8) -> Self {
+        self.add(|lpp| lpp.add_digital_output(channel, value))
+    }
+
+    /// Adds an analog input (see [`CayenneLPP::add_analog_input`]).
+    pub fn analog_input(self, channel: u8, value: f32) -> Self {
+        self.add(|lpp| lpp.add_analog_input(channel, value))
+    }
+
+    /// Adds an analog output (see [`CayenneLPP::add_analog_output`]).
+    pub fn analog_output(self, channel: u8, value: f32) -> Self {
+        self.add(|lpp| lpp.add_analog_output(channel, value))
+    }
+
+    /// Adds a generic sensor (see [`CayenneLPP::add_generic_sensor`]).
+    pub fn generic_sensor(self, channel: u8, value: u32) -> Self {
+        self.add(|lpp| lpp.add_generic_sensor(channel, value))
+    }
+
+    /// Adds a luminosity (see [`CayenneLPP::add_luminosity`]).
+    pub fn luminosity(self, channel: u8, lux: u16) -> Self {
+        self.add(|lpp| lpp.add_luminosity(channel, lux))
+    }
+
+    /// Adds a presence (see [`CayenneLPP::add_presence`]).
+    pub fn presence(self, channel: u8, value: u8) -> Self {
+        self.add(|lpp| lpp.add_presence(channel, value))
+    }
+
+    /// Adds a temperature (see [`CayenneLPP::add_temperature`]).
+    pub fn temperature(self, channel: u8, celsius: f32) -> Self {
+        self.add(|lpp| lpp.add_temperature(channel, celsius))
+    }
+
+    /// Adds a relative humidity (see [`CayenneLPP::add_relative_humidity`]).
+    pub fn relative_humidity(self, channel: u8, relative_humidity: f32) -> Self {
+        self.add(|lpp| lpp.add_relative_humidity(channel, relative_humidity))
+    }
+
+    /// Adds an accelerometer (see [`CayenneLPP::add_accelerometer`]).
+    pub fn accelerometer(self, channel: u8, x: f32, y: f32, z: f32) -> Self {
+        self.add(|lpp| lpp.add_accelerometer(channel, x, y, z))
+    }
+
+    /// Adds a barometric pressure (see [`CayenneLPP::add_barometric_pressure`]).
+    pub fn barometric_pressure(self, channel: u8, hpa: f32) -> Self {
+        self.add(|lpp| lpp.add_barometric_pressure(channel, hpa))
+    }
+
+    /// Adds a voltage (see [`CayenneLPP::add_voltage`]).
+    pub fn voltage(self, channel: u8, voltage: f32) -> Self {
+        self.add(|lpp| lpp.add_voltage(channel, voltage))
+    }
+
+    /// Adds a current (see [`CayenneLPP::add_current`]).
+    pub fn current(self, channel: u8, amperage: f32) -> Self {
+        self.add(|lpp| lpp.add_current(channel, amperage))
+    }
+
+    /// Adds a frequency (see [`CayenneLPP::add_frequency`]).
+    pub fn frequency(self, channel: u8, frequency: u32) -> Self {
+        self.add(|lpp| lpp.add_frequency(channel, frequency))
+    }
+
+    /// Adds a percentage (see [`CayenneLPP::add_percentage`]).
+    pub fn percentage(self, channel: u8, percentage: u8) -> Self {
+        self.add(|lpp| lpp.add_percentage(channel, percentage))
+    }
+
+    /// Adds an altitude (see [`CayenneLPP::add_altitude`]).
+    pub fn altitude(self, channel: u8, altitude: i16) -> Self {
+        self.add(|lpp| lpp.add_altitude(channel, altitude))
+    }
+
+    /// Adds a power (see [`CayenneLPP::add_power`]).
+    pub fn power(self, channel: u8, power: u16) -> Self {
+        self.add(|lpp| lpp.add_power(channel, power))
+    }
+
+    /// Adds a distance (see [`CayenneLPP::add_distance`]).
+    pub fn distance(self, channel: u8, distance: u32) -> Self {
+        self.add(|lpp| lpp.add_distance(channel, distance))
+    }
+
+    /// Adds an energy (see [`CayenneLPP::add_energy`]).
+    pub fn energy(self, channel: u8, energy: u32) -> Self {
+        self.add(|lpp| lpp.add_energy(channel, energy))
+    }
+
+    /// Adds a direction (see [`CayenneLPP::add_direction`]).
+    pub fn direction(self, channel: u8, direction: u16) -> Self {
+        self.add(|lpp| lpp.add_direction(channel, direction))
+    }
+
+    /// Adds a unix time (see [`CayenneLPP::add_unixtime`]).
+    pub fn unixtime(self, channel: u8, unixtime: u32) -> Self {
+        self.add(|lpp| lpp.add_unixtime(channel, unixtime))
+    }
+
+    /// Adds a gyrometer (see [`CayenneLPP::add_gyrometer`]).
+    pub fn gyrometer(self, channel: u8, x: f32, y: f32, z: f32) -> Self {
+        self.add(|lpp| lpp.add_gyrometer(channel, x, y, z))
+    }
+
+    /// Adds a GPS position (see [`CayenneLPP::add_gps`]).
+    pub fn gps(self, channel: u8, latitude: f32, longitude: f32, meters: f32) -> Self {
+        self.add(|lpp| lpp.add_gps(channel, latitude, longitude, meters))
+    }
+
+    /// Adds a switch (see [`CayenneLPP::add_switch`]).
+    pub fn switch(self, channel: u8, value: bool) -> Self {
+        self.add(|lpp| lpp.add_switch(channel, value))
+    }
+
+    /// Adds a concentration (see [`CayenneLPP::add_concentration`]).
+    pub fn concentration(self, channel: u8, concentration: u16) -> Self {
+        self.add(|lpp| lpp.add_concentration(channel, concentration))
+    }
+
+    /// Adds a color (see [`CayenneLPP::add_color`]).
+    pub fn color(self, channel: u8, red: u8, green: u8, blue: u8) -> Self {
+        self.add(|lpp| lpp.add_color(channel, red, green, blue))
+    }
+
+    /// Adds a custom type (see [`CayenneLPP::add_custom`]).
+    pub fn custom(self, channel: u8, type_code: u8, value: f32) -> Self {
+        self.add(|lpp| lpp.add_custom(channel, type_code, value))
+    }
+}
@@ -0,0 +1,179 @@
+use crate::error::Error;
+use crate::lpp_type::LppType;
+
+/// A user-defined data type, e.g., a vendor-specific sensor value that is not part of the Cayenne LPP specification.
+///
+/// A custom type consists of a type code, the size of its entries and the resolution that is used to scale between
+/// the physical value and the raw integer value that is written to the payload. The raw value is stored as a big endian
+/// integer by default, which can be changed by overriding the [`encode`](CayenneLPPCustomType::encode) and
+/// [`decode`](CayenneLPPCustomType::decode) functions.
+///
+/// Custom types have to be registered in a [`CayenneLPPRegistry`] to be used by the encoder and the decoder.
+pub trait CayenneLPPCustomType: Sync {
+    /// The type code of the custom type. Type codes that are already handled by this library can not be overridden.
+    fn type_code(&self) -> u8;
+
+    /// Size of an entry of the custom type including channel and data type
+    fn size(&self) -> usize;
+
+    /// The physical value of a single step of the raw value (e.g., 0.1 for a resolution of 0.1 kPa)
+    fn resolution(&self) -> f32;
+
+    /// Whether the raw value is a signed integer. This is only used by the default encode and decode functions.
+    fn signed(&self) -> bool {
+        false
+    }
+
+    /// The range `min..=max` of the raw values that can be encoded. Values outside of this range are rejected,
+    /// clamped or wrapped according to the overflow policy of the encoder before they are passed to `encode()`.
+    ///
+    /// The default implementation returns the range of the big endian integer of the default `encode()` function.
+    fn raw_range(&self) -> (i64, i64) {
+        let bits = 8 * self.size().saturating_sub(2).clamp(1, 7) as u32;
+        if self.signed() {
+            (-(1i64 << (bits - 1)), (1i64 << (bits - 1)) - 1)
+        } else {
+            (0, (1i64 << bits) - 1)
+        }
+    }
+
+    /// Writes the raw value to the data bytes of an entry. The slice has a length of `size() - 2`.
+    ///
+    /// The default implementation writes a big endian integer with up to 7 bytes and returns `Error::OutOfRange` if
+    /// the value is not within `raw_range()`.
+    fn encode(&self, raw: i64, data: &mut [u8]) -> Result<(), Error> {
+        if data.is_empty() || data.len() > 7 {
+            return Err(Error::OutOfRange);
+        }
+
+        let (min, max) = self.raw_range();
+        if !(min..=max).contains(&raw) {
+            return Err(Error::OutOfRange);
+        }
+
+        let raw_bytes = raw.to_be_bytes();
+        data.copy_from_slice(&raw_bytes[raw_bytes.len() - data.len()..]);
+
+        Ok(())
+    }
+
+    /// Reads the raw value from the data bytes of an entry. The slice has a length of `size() - 2`.
+    ///
+    /// The default implementation reads a big endian integer with up to 7 bytes.
+    fn decode(&self, data: &[u8]) -> Result<i64, Error> {
+        if data.is_empty() || data.len() > 7 {
+            return Err(Error::OutOfRange);
+        }
+
+        let mut raw: i64 = 0;
+        for byte in data {
+            raw = (raw << 8) | *byte as i64;
+        }
+
+        // Perform sign extension if the highest bit of the value is set
+        if self.signed() && data[0] & 0x80 == 0x80 {
+            raw -= 1i64 << (8 * data.len());
+        }
+
+        Ok(raw)
+    }
+}
+
+/// A custom type that stores its value as a scaled big endian integer. This covers most vendor-specific sensor
+/// values, so it is not necessary to implement [`CayenneLPPCustomType`] for them.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct CayenneLPPScaledType {
+    /// The type code of the custom type
+    pub type_code: u8,
+
+    /// Size of an entry of the custom type including channel and data type
+    pub size: usize,
+
+    /// The physical value of a single step of the raw value
+    pub resolution: f32,
+
+    /// Whether the raw value is a signed integer
+    pub signed: bool
+}
+
+impl CayenneLPPCustomType for CayenneLPPScaledType {
+    fn type_code(&self) -> u8 {
+        self.type_code
+    }
+
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn resolution(&self) -> f32 {
+        self.resolution
+    }
+
+    fn signed(&self) -> bool {
+        self.signed
+    }
+}
+
+/// A set of custom types that can be used by [`CayenneLPP`](crate::CayenneLPP) and
+/// [`CayenneLPPDecoder`](crate::CayenneLPPDecoder). Since the registry only borrows the custom types, it can be
+/// created statically without a memory allocator:
+///
+/// ```
+/// use cayenne_lpp::{CayenneLPPRegistry, CayenneLPPScaledType};
+///
+/// static SOIL_MOISTURE_TENSION: CayenneLPPScaledType =
+///     CayenneLPPScaledType { type_code: 0xC8, size: 4, resolution: 0.1, signed: false };
+///
+/// static REGISTRY: CayenneLPPRegistry = CayenneLPPRegistry::new(&[&SOIL_MOISTURE_TENSION]);
+/// ```
+#[derive(Clone, Copy)]
+pub struct CayenneLPPRegistry<'a> {
+    types: &'a [&'a dyn CayenneLPPCustomType]
+}
+
+impl<'a> CayenneLPPRegistry<'a> {
+    /// Creates a new registry for the given custom types. The registry can be created statically, so the custom types
+    /// are not validated here: custom types that use the type code of a data type of this library, or whose size is
+    /// smaller than the channel and the type code, are ignored. Use [`try_new`](CayenneLPPRegistry::try_new) to reject
+    /// them instead.
+    pub const fn new(types: &'a [&'a dyn CayenneLPPCustomType]) -> Self {
+        CayenneLPPRegistry {
+            types
+        }
+    }
+
+    /// Creates a new registry for the given custom types, or returns `Error::UnhandledType` with the type code of the
+    /// first custom type that cannot be registered (see [`new`](CayenneLPPRegistry::new)).
+    pub fn try_new(types: &'a [&'a dyn CayenneLPPCustomType]) -> Result<Self, Error> {
+        match types.iter().find(|custom_type| !is_valid(**custom_type)) {
+            Some(custom_type) => Err(Error::UnhandledType(custom_type.type_code())),
+            None => Ok(CayenneLPPRegistry::new(types)),
+        }
+    }
+
+    /// Creates a registry that does not contain any custom types.
+    pub const fn empty() -> Self {
+        CayenneLPPRegistry {
+            types: &[]
+        }
+    }
+
+    /// Returns the custom type with the given type code, if it is registered and valid.
+    pub fn get(&self, type_code: u8) -> Option<&'a dyn CayenneLPPCustomType> {
+        self.types.iter()
+            .find(|custom_type| custom_type.type_code() == type_code && is_valid(**custom_type))
+            .copied()
+    }
+}
+
+/// Returns whether the custom type can be used, i.e., it does not override a data type of this library and its entries
+/// contain at least the channel and the type code.
+fn is_valid(custom_type: &dyn CayenneLPPCustomType) -> bool {
+    LppType::from_code(custom_type.type_code()).is_none() && custom_type.size() >= 2
+}
+
+impl core::fmt::Debug for CayenneLPPRegistry<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.types.iter().map(|custom_type| custom_type.type_code())).finish()
+    }
+}
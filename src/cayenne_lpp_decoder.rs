@@ -0,0 +1,151 @@
+use crate::cayenne_lpp_custom_type::CayenneLPPRegistry;
+use crate::cayenne_lpp_into_iterator::{CayenneLPPIntoFailableIterator, CayenneLPPIterator, CayenneLPPRawIterator};
+use crate::cayenne_lpp_scalar::CayenneLPPScalar;
+use crate::error::Error;
+#[cfg(feature = "alloc")]
+use crate::error::{DecodeError, DecodeErrorKind};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// Read-only decoder for a Cayenne LPP payload. In contrast to iterating over a [`CayenneLPP`](crate::CayenneLPP)
+/// instance, the decoder only borrows the bytes immutably, so it can be used directly on frames that were received
+/// from a network server without copying them into a mutable scratch buffer first.
+#[derive(Debug, Clone, Copy)]
+pub struct CayenneLPPDecoder<'a> {
+    buffer: &'a [u8],
+    registry: CayenneLPPRegistry<'a>,
+    extra_types: &'a [CayenneLPPExtraType]
+}
+
+/// Size of a type code that is not handled by this library, e.g., a vendor extension. If the size is registered with
+/// [`CayenneLPPDecoder::with_extra_types`], entries of this type are skipped instead of stopping the decoding.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct CayenneLPPExtraType {
+    /// The type code of the extra type
+    pub type_code: u8,
+
+    /// Size of an entry of the extra type including channel and data type
+    pub size: usize
+}
+
+impl CayenneLPPExtraType {
+    /// Returns whether an entry of the extra type contains at least the channel and the type code, so that it can be
+    /// skipped.
+    pub(crate) fn is_valid(&self) -> bool {
+        self.size >= 2
+    }
+}
+
+impl<'a> CayenneLPPDecoder<'a> {
+    /// Creates a new decoder for the given payload bytes.
+    pub fn new(buffer: &'a [u8]) -> Self {
+        CayenneLPPDecoder {
+            buffer,
+            registry: CayenneLPPRegistry::empty(),
+            extra_types: &[]
+        }
+    }
+
+    /// Sets the registry with the custom types that shall be unpacked by the iterators. Entries of custom types are
+    /// returned as [`CayenneLPPValue::Custom`](crate::CayenneLPPValue::Custom).
+    pub fn with_registry(self, registry: CayenneLPPRegistry<'a>) -> Self {
+        CayenneLPPDecoder {
+            registry,
+            ..self
+        }
+    }
+
+    /// Enables the lenient decoding mode. Entries with a type code that is not handled by this library, but that is
+    /// contained in `extra_types`, are skipped. The failable iterator reports them as
+    /// [`DecodeErrorKind::SkippedType`](crate::error::DecodeErrorKind::SkippedType) and continues with the next
+    /// entry, the infallable iterator silently ignores them. Extra types whose size is smaller than the channel and
+    /// the type code are ignored; use [`try_with_extra_types`](CayenneLPPDecoder::try_with_extra_types) to reject
+    /// them instead.
+    pub fn with_extra_types(self, extra_types: &'a [CayenneLPPExtraType]) -> Self {
+        CayenneLPPDecoder {
+            extra_types,
+            ..self
+        }
+    }
+
+    /// Enables the lenient decoding mode like [`with_extra_types`](CayenneLPPDecoder::with_extra_types), or returns
+    /// `Error::UnhandledType` with the type code of the first extra type whose size is smaller than the channel and the
+    /// type code.
+    pub fn try_with_extra_types(self, extra_types: &'a [CayenneLPPExtraType]) -> Result<Self, Error> {
+        match extra_types.iter().find(|extra_type| !extra_type.is_valid()) {
+            Some(extra_type) => Err(Error::UnhandledType(extra_type.type_code)),
+            None => Ok(self.with_extra_types(extra_types)),
+        }
+    }
+
+    /// Returns the payload bytes that are decoded by this decoder.
+    pub fn payload_slice(&self) -> &'a [u8] {
+        self.buffer
+    }
+
+    /// Creates a failable iterator over the scalars of the payload. Every item is a `Result`, so errors in the
+    /// payload (e.g., unhandled type codes or truncated values) are reported to the caller.
+    pub fn iter(&self) -> CayenneLPPIntoFailableIterator<'a> {
+        CayenneLPPIntoFailableIterator { raw_iterator: self.raw_iter() }
+    }
+
+    /// Creates a failable iterator over the raw values of the payload. The values are returned in the integer units
+    /// of the payload, so no floating point operations are used to unpack them.
+    pub fn raw_iter(&self) -> CayenneLPPRawIterator<'a> {
+        CayenneLPPRawIterator {
+            buffer: self.buffer,
+            registry: self.registry,
+            extra_types: self.extra_types,
+            index: 0
+        }
+    }
+
+    /// Creates an infallable iterator over the scalars of the payload. The iteration stops at the first scalar that
+    /// cannot be unpacked.
+    pub fn infailable_iter(&self) -> CayenneLPPIterator<'a> {
+        CayenneLPPIterator { failable_iterator: self.iter() }
+    }
+
+    /// Decodes all scalars of the payload into a vector. In contrast to the infallable iterator, the first error in
+    /// the payload is returned instead of a partially decoded payload. Entries that are skipped in the lenient
+    /// decoding mode are no errors.
+    #[cfg(feature = "alloc")]
+    pub fn decode_to_vec(&self) -> Result<Vec<CayenneLPPScalar>, Error> {
+        let mut scalars = Vec::new();
+
+        for scalar in self.iter() {
+            match scalar {
+                Ok(scalar) => scalars.push(scalar),
+                Err(Error::Decode(DecodeError { kind: DecodeErrorKind::SkippedType { .. }, .. })) => continue,
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(scalars)
+    }
+}
+
+/// Decodes all scalars of the payload into a vector, or returns the first error in the payload. Use
+/// [`CayenneLPPDecoder::decode_to_vec`] to decode custom types or to skip extra types.
+#[cfg(feature = "alloc")]
+pub fn decode_to_vec(buffer: &[u8]) -> Result<Vec<CayenneLPPScalar>, Error> {
+    CayenneLPPDecoder::new(buffer).decode_to_vec()
+}
+
+impl<'a> IntoIterator for CayenneLPPDecoder<'a> {
+    type Item = Result<CayenneLPPScalar, Error>;
+    type IntoIter = CayenneLPPIntoFailableIterator<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &CayenneLPPDecoder<'a> {
+    type Item = Result<CayenneLPPScalar, Error>;
+    type IntoIter = CayenneLPPIntoFailableIterator<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
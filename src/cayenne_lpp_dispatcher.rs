@@ -0,0 +1,137 @@
+use crate::cayenne_lpp_custom_type::CayenneLPPRegistry;
+use crate::cayenne_lpp_decoder::CayenneLPPDecoder;
+use crate::cayenne_lpp_scalar::CayenneLPPValue;
+use crate::error::{DispatchError, Error};
+use crate::lpp_type::LppType;
+
+/// A command of a downlink for an actuator. Only the data types of the Cayenne LPP specification that control an
+/// output can be sent to a device.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum CayenneLPPCommand {
+    /// Sets a digital output
+    DigitalOutput(u8),
+
+    /// Sets an analog output
+    AnalogOutput(f32),
+
+    /// Turns a switch on or off
+    Switch(bool),
+
+    /// Sets a color, e.g., of an RGB LED
+    Color(u8, u8, u8),
+}
+
+impl CayenneLPPCommand {
+    /// Returns the data type of the command.
+    pub const fn lpp_type(&self) -> LppType {
+        match self {
+            CayenneLPPCommand::DigitalOutput(_) => LppType::DigitalOutput,
+            CayenneLPPCommand::AnalogOutput(_) => LppType::AnalogOutput,
+            CayenneLPPCommand::Switch(_) => LppType::Switch,
+            CayenneLPPCommand::Color(..) => LppType::Color,
+        }
+    }
+
+    /// Returns the command of a value, or `None` if the data type of the value does not control an output.
+    pub const fn from_value(value: CayenneLPPValue) -> Option<Self> {
+        match value {
+            CayenneLPPValue::DigitalOutput(value) => Some(CayenneLPPCommand::DigitalOutput(value)),
+            CayenneLPPValue::AnalogOutput(value) => Some(CayenneLPPCommand::AnalogOutput(value)),
+            CayenneLPPValue::Switch(value) => Some(CayenneLPPCommand::Switch(value)),
+            CayenneLPPValue::Color(r, g, b) => Some(CayenneLPPCommand::Color(r, g, b)),
+            _ => None,
+        }
+    }
+}
+
+/// Handler of the commands for an actuator, e.g., a driver of a relay or an LED. The handler is registered for a
+/// channel and a data type in a [`CayenneLPPRoute`], so it only receives commands of this data type. Closures with
+/// the same arguments as [`handle`](CayenneLPPHandler::handle) are handlers, too.
+pub trait CayenneLPPHandler {
+    /// Executes a command that was received on the channel of the handler.
+    fn handle(&mut self, channel: u8, command: CayenneLPPCommand);
+}
+
+impl<F: FnMut(u8, CayenneLPPCommand)> CayenneLPPHandler for F {
+    fn handle(&mut self, channel: u8, command: CayenneLPPCommand) {
+        self(channel, command)
+    }
+}
+
+/// Registers a handler for the commands of a data type on a channel.
+pub struct CayenneLPPRoute<'h> {
+    /// The channel of the actuator
+    pub channel: u8,
+
+    /// The data type of the commands, e.g., [`LppType::DigitalOutput`]
+    pub lpp_type: LppType,
+
+    /// The handler that executes the commands
+    pub handler: &'h mut dyn CayenneLPPHandler,
+}
+
+/// Routes the commands of a downlink to the handlers of the actuators of a device. The routes are provided by the
+/// firmware, so no memory allocator is needed:
+///
+/// ```
+/// use cayenne_lpp::*;
+///
+/// let mut relay = false;
+/// let mut set_relay = |_channel: u8, command: CayenneLPPCommand| relay = command == CayenneLPPCommand::Switch(true);
+///
+/// let mut routes = [CayenneLPPRoute { channel: 4, lpp_type: LppType::Switch, handler: &mut set_relay }];
+/// let mut dispatcher = CayenneLPPDispatcher::new(&mut routes);
+/// assert_eq!(1, dispatcher.dispatch(&[0x04, LPP_SWITCH, 0x01], |_error| {}));
+/// assert!(relay);
+/// ```
+pub struct CayenneLPPDispatcher<'a, 'h> {
+    routes: &'a mut [CayenneLPPRoute<'h>]
+}
+
+impl<'a, 'h> CayenneLPPDispatcher<'a, 'h> {
+    /// Creates a new dispatcher for the given routes. If several routes have the same channel and data type, the
+    /// commands are sent to the first one.
+    pub fn new(routes: &'a mut [CayenneLPPRoute<'h>]) -> Self {
+        CayenneLPPDispatcher { routes }
+    }
+
+    /// Decodes the downlink and sends every command to the handler of its channel and data type. Entries that
+    /// cannot be dispatched are passed to `report`: commands without a route, data types that do not control an
+    /// output (e.g., a temperature) and entries that cannot be decoded. Like the failable iterator, the dispatching
+    /// continues after an error unless the rest of the downlink cannot be decoded. Returns the number of commands
+    /// that were sent to a handler.
+    pub fn dispatch(&mut self, downlink: &[u8], mut report: impl FnMut(DispatchError)) -> usize {
+        let mut dispatched = 0;
+
+        for scalar in CayenneLPPDecoder::new(downlink).raw_iter() {
+            let scalar = match scalar {
+                Ok(scalar) => scalar,
+                Err(Error::Decode(error)) => {
+                    report(DispatchError::Decode(error));
+                    continue
+                },
+                // the iterator only returns decode errors
+                Err(_) => continue,
+            };
+
+            let value = scalar.value.to_value(&CayenneLPPRegistry::empty());
+            let Some(command) = CayenneLPPCommand::from_value(value) else {
+                report(DispatchError::InputOnlyType { channel: scalar.channel, type_code: scalar.value.type_code() });
+                continue
+            };
+
+            let lpp_type = command.lpp_type();
+            let route = self.routes.iter_mut()
+                .find(|route| route.channel == scalar.channel && route.lpp_type == lpp_type);
+            let Some(route) = route else {
+                report(DispatchError::UnknownChannel { channel: scalar.channel, type_code: lpp_type.code() });
+                continue
+            };
+
+            route.handler.handle(scalar.channel, command);
+            dispatched += 1;
+        }
+
+        dispatched
+    }
+}
@@ -1,3 +1,10 @@
+use crate::cayenne_lpp_custom_type::CayenneLPPRegistry;
+use crate::error::Error;
+use crate::lpp_type::{LppField, LppType, MAX_FIELDS};
+use crate::overflow::{Encoded, Overflow};
+use crate::rounding::Rounding;
+use crate::{CayenneLPP, CayenneLPPDecoder};
+
 /// Enumeration of the CayenneLPP value that are supported by this library
 #[derive(PartialEq, Clone, Copy)]
 pub enum CayenneLPPValue {
@@ -77,8 +84,29 @@ pub enum CayenneLPPValue {
 
     /// Data type of a switch value
     Switch(bool),
+
+    /// Data type of a custom type with its type code and value (see [`CayenneLPPCustomType`](crate::CayenneLPPCustomType))
+    Custom(u8, f32),
+}
+
+impl CayenneLPPValue {
+    /// Returns the size of the value in the payload including channel and data type. The size of a custom type is
+    /// defined by its registry, so `None` is returned for it.
+    pub const fn encoded_size(&self) -> Option<usize> {
+        match self.kind() {
+            Some(lpp_type) => Some(lpp_type.size()),
+            None => None
+        }
+    }
+
+    /// Returns the value of the field with the given index, e.g., 0 for the x axis of an accelerometer (see
+    /// [`LppType::field`]), or `None` if the data type has fewer fields. Custom types have a single field.
+    pub fn field_value(&self, index: usize) -> Option<f64> {
+        self.field_number(index).map(FieldNumber::to_f64)
+    }
 }
 
+
 impl core::fmt::Debug for CayenneLPPValue {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
@@ -175,6 +203,9 @@ impl core::fmt::Debug for CayenneLPPValue {
             },
             Self::Switch(arg0) => {f.debug_tuple("Switch").field(arg0).finish()
             },
+            Self::Custom(arg0, arg1) => {
+                f.debug_tuple("Custom").field(arg0).field(arg1).finish()
+            },
         }
     }
 }
@@ -182,10 +213,476 @@ impl core::fmt::Debug for CayenneLPPValue {
 /// Single value parsed from a CayenneLPP data structure,
 /// including the enumeration of its value and it's channel.
 #[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CayenneLPPScalar {
     /// The channel value parsed from a data structure
     pub channel: u8,
 
     /// Value of the calue parsed from a data structure
     pub value: CayenneLPPValue
-}
\ No newline at end of file
+}
+
+impl CayenneLPPScalar {
+    /// Encodes the scalar into the beginning of the buffer and returns the number of written bytes. The scalar is
+    /// written like by `CayenneLPP::add_scalar()` with the default rounding and overflow policy, so no `CayenneLPP`
+    /// instance is needed to encode a single value. Custom types cannot be encoded without their registry and return
+    /// `Error::UnhandledType`.
+    pub fn encode_into(&self, buffer: &mut [u8]) -> Result<usize, Error> {
+        let mut lpp = CayenneLPP::new(buffer);
+        lpp.add_scalar(self)?;
+
+        Ok(lpp.payload_slice().len())
+    }
+
+    /// Decodes the scalar at the beginning of the bytes and returns it together with the number of consumed bytes.
+    /// Bytes after the scalar are ignored, so a payload can be decoded by calling this function for the remaining
+    /// bytes. An empty buffer returns `Error::BufferUnderrun`, an invalid entry the same error as the decoder.
+    pub fn decode(bytes: &[u8]) -> Result<(CayenneLPPScalar, usize), Error> {
+        let mut iter = CayenneLPPDecoder::new(bytes).iter();
+        let scalar = iter.next().ok_or(Error::BufferUnderrun)??;
+
+        Ok((scalar, iter.raw_iterator.index))
+    }
+}
+
+/// Enumeration of the CayenneLPP values in the raw integer units of the payload. This representation is used by the
+/// `add_*_raw()` functions and the raw iterators, which do not need any floating point operations. This makes them
+/// suitable for microcontrollers without a floating point unit.
+///
+/// The physical value is the raw value multiplied by the resolution of the data type, e.g., a raw temperature of
+/// `255` means 25.5 °C.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CayenneLPPRawValue {
+    /// Data type of a digital input
+    DigitalInput(u8),
+
+    /// Data type of a digital output
+    DigitalOutput(u8),
+
+    /// Data type of an analog input (0.01)
+    AnalogInput(i16),
+
+    /// Data type of an analog output (0.01)
+    AnalogOutput(i16),
+
+    /// Data type of a generic sensor
+    GenericSensor(u32),
+
+    /// Data type of a luminosity value (1 lux)
+    Luminosity(u16),
+
+    /// Data type of a presence sensor
+    Presence(u8),
+
+    /// Data type of a temperature value (0.1 °C)
+    Temperature(i16),
+
+    /// Data type of a relative humidity value (0.5 %)
+    RelativeHumidity(u8),
+
+    /// Data type of accelerometer values (0.001 G per axis)
+    Accelerometer(i16, i16, i16),
+
+    /// Data type of a barometric pressure value (0.1 hPa)
+    BarometricPressure(u16),
+
+    /// Data type of a voltage value (0.01 V)
+    Voltage(u16),
+
+    /// Data type of a current value (1 mA)
+    Current(u16),
+
+    /// Data type of a frequency value (1 Hz)
+    Frequency(u32),
+
+    /// Data type of a percentage (1 %)
+    Percentage(u8),
+
+    /// Data type of an altitude (1 m)
+    Altitude(i16),
+
+    /// Data type of a concentration (1 ppm)
+    Concentration(u16),
+
+    /// Data type of a power value (1 W)
+    Power(u16),
+
+    /// Data type of a distance value (1 mm)
+    Distance(u32),
+
+    /// Data type of an energy value (1 Wh)
+    Energy(u32),
+
+    /// Data type of a direction value (1 °)
+    Direction(u16),
+
+    /// Data type of a time (unix timestamp)
+    UnixTime(u32),
+
+    /// Data type of gyrometer values (0.01 °/s per axis)
+    Gyrometer(u16, u16, u16),
+
+    /// Data type of a color value
+    Color(u8, u8, u8),
+
+    /// Data type of GPS value (0.0001 ° for latitude and longitude, 0.01 m for the altitude)
+    GPS(i32, i32, i32),
+
+    /// Data type of a switch value
+    Switch(bool),
+
+    /// Data type of a custom type with its type code and value (in steps of the resolution of the custom type)
+    Custom(u8, i64),
+}
+
+/// Conversion between the integer values of [`CayenneLPPRawValue`] and the raw values of the payload.
+pub(crate) trait RawField: Copy {
+    /// Converts the raw value of the payload, which is in the range of its field.
+    fn from_raw(raw: i64) -> Self;
+
+    /// Converts the value into the raw value of the payload.
+    fn into_raw(self) -> i64;
+
+    /// Converts an integer into the value, or returns `None` if the integer is not representable by the value.
+    fn try_from_raw(raw: i64) -> Option<Self>;
+
+    /// Converts the value into a number of the same kind.
+    fn into_number(self) -> FieldNumber;
+}
+
+macro_rules! raw_fields {
+    ($($raw:ty),*) => {
+        $(
+            impl RawField for $raw {
+                fn from_raw(raw: i64) -> Self {
+                    raw as $raw
+                }
+
+                fn into_raw(self) -> i64 {
+                    self.into()
+                }
+
+                fn try_from_raw(raw: i64) -> Option<Self> {
+                    raw.try_into().ok()
+                }
+
+                fn into_number(self) -> FieldNumber {
+                    FieldNumber::Integer(self.into())
+                }
+            }
+        )*
+    };
+}
+
+raw_fields!(u8, u16, i16, u32, i32);
+
+impl RawField for bool {
+    fn from_raw(raw: i64) -> Self {
+        raw != 0
+    }
+
+    fn into_raw(self) -> i64 {
+        self.into()
+    }
+
+    fn try_from_raw(raw: i64) -> Option<Self> {
+        match raw {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None
+        }
+    }
+
+    fn into_number(self) -> FieldNumber {
+        FieldNumber::Bool(self)
+    }
+}
+
+/// Value of a field in the kind of number of its type, e.g., to serialize a switch as a boolean and a temperature as
+/// a floating point number.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(crate) enum FieldNumber {
+    Bool(bool),
+    Integer(i64),
+    Float(f32),
+}
+
+impl FieldNumber {
+    /// Converts the number into a floating point number without loss.
+    pub(crate) fn to_f64(self) -> f64 {
+        match self {
+            FieldNumber::Bool(value) => value.into(),
+            FieldNumber::Integer(value) => value as f64,
+            FieldNumber::Float(value) => value.into(),
+        }
+    }
+}
+
+/// Conversion between the values of [`CayenneLPPValue`] and the raw values of the payload.
+pub(crate) trait FieldValue: Copy {
+    /// Converts the raw value of the payload into the value of the field.
+    fn from_field(raw: i64, field: &LppField) -> Self;
+
+    /// Converts the value into the raw value of the field, using the rounding and the overflow policy.
+    fn to_field(self, field: &LppField, rounding: Rounding, overflow: Overflow) -> Result<(i64, Encoded), Error>;
+
+    /// Converts a number, e.g., of a JSON document, into the value, or returns `None` if the number is not
+    /// representable by the type of the value.
+    fn from_number(number: f64) -> Option<Self>;
+
+    /// Converts the value into a number without loss.
+    fn to_number(self) -> FieldNumber;
+}
+
+impl<T: RawField> FieldValue for T {
+    fn from_field(raw: i64, _field: &LppField) -> Self {
+        T::from_raw(raw)
+    }
+
+    #[inline(always)]
+    fn to_field(self, field: &LppField, _rounding: Rounding, overflow: Overflow) -> Result<(i64, Encoded), Error> {
+        overflow.limit(self.into_raw(), field.raw_min, field.raw_max)
+    }
+
+    fn from_number(number: f64) -> Option<Self> {
+        let raw = number as i64;
+        if raw as f64 != number {
+            return None
+        }
+
+        T::try_from_raw(raw)
+    }
+
+    fn to_number(self) -> FieldNumber {
+        self.into_number()
+    }
+}
+
+impl FieldValue for f32 {
+    fn from_field(raw: i64, field: &LppField) -> Self {
+        raw as f32 / field.factor
+    }
+
+    #[inline(always)]
+    fn to_field(self, field: &LppField, rounding: Rounding, overflow: Overflow) -> Result<(i64, Encoded), Error> {
+        overflow.scale(self, field.factor, rounding, field.raw_min, field.raw_max)
+    }
+
+    fn from_number(number: f64) -> Option<Self> {
+        Some(number as f32)
+    }
+
+    fn to_number(self) -> FieldNumber {
+        FieldNumber::Float(self)
+    }
+}
+
+// The conversions of the fields are always inlined, so the metadata of a data type is
+// resolved at compile time if the data type is known, e.g., in the `add_*()` functions.
+
+/// Converts the values of a data type into the raw values of its fields.
+#[inline(always)]
+pub(crate) fn to_fields<T: FieldValue, const N: usize>(values: [T; N], lpp_type: LppType, rounding: Rounding,
+    overflow: Overflow) -> Result<([i64; N], Encoded), Error> {
+    let mut raw = [0; N];
+    let mut encoded = Encoded::Exact;
+
+    for ((raw, value), field) in raw.iter_mut().zip(values).zip(lpp_type.info().fields) {
+        let (field_raw, field_encoded) = value.to_field(field, rounding, overflow)?;
+        *raw = field_raw;
+        encoded = encoded.and(field_encoded);
+    }
+
+    Ok((raw, encoded))
+}
+
+/// Converts the raw values of the fields of a data type into its values.
+#[inline(always)]
+fn from_fields<T: FieldValue, const N: usize>(raw: [i64; N], lpp_type: LppType) -> [T; N] {
+    let fields = lpp_type.info().fields;
+    core::array::from_fn(|index| T::from_field(raw[index], &fields[index]))
+}
+
+/// Reads the raw values of the fields of a data type from the bytes after its header, or returns `None` if one of
+/// them is out of the range of a checked field. The fields are constant, so the reads are unrolled for every data type
+/// and the bytes do not need any bounds checks.
+#[inline(always)]
+fn read_fields<const N: usize, const SIZE: usize>(bytes: &[u8; SIZE], fields: &[LppField]) -> Option<[i64; N]> {
+    let mut raw = [0; N];
+    let mut offset = 0;
+
+    for (raw, field) in raw.iter_mut().zip(fields) {
+        *raw = field.read(&bytes[offset..offset + field.bytes]);
+        offset += field.bytes;
+
+        if field.checked && !field.contains(*raw) {
+            return None
+        }
+    }
+
+    Some(raw)
+}
+
+/// Copies the raw values of the fields into an array with the maximum number of fields.
+fn pad<const N: usize>(raw: [i64; N]) -> [i64; MAX_FIELDS] {
+    core::array::from_fn(|index| if index < N { raw[index] } else { 0 })
+}
+
+/// Defines the conversions of all data types between [`CayenneLPPValue`], [`CayenneLPPRawValue`] and the raw values
+/// of the fields in the payload. Every variant is mapped to the data type with the same name in [`LppType`], whose
+/// fields in [`LPP_TYPES`](crate::LPP_TYPES) define the scaling and the range of the values. The decoders, the serde
+/// representation and the TTN JSON only use these conversions and the table. A new data type additionally needs its
+/// variants, its entries in [`LppType::ALL`] and the table, its arms in the `Debug` impls of the values and its
+/// `add_*()` function and builder method.
+macro_rules! lpp_values {
+    ($($variant:ident($($value:ident),+)),* $(,)?) => {
+        impl CayenneLPPValue {
+            /// Returns the data type of the value, or `None` for a custom type, since custom types are not handled by
+            /// this library.
+            pub const fn kind(&self) -> Option<LppType> {
+                match self {
+                    $(Self::$variant(..) => Some(LppType::$variant),)*
+                    Self::Custom(..) => None,
+                }
+            }
+
+            /// Returns the value of the field with the given index in the kind of number of its type, or `None` if
+            /// the data type has fewer fields (see [`CayenneLPPValue::field_value`]).
+            pub(crate) fn field_number(&self, index: usize) -> Option<FieldNumber> {
+                match *self {
+                    $(Self::$variant($($value),+) => [$($value.to_number()),+].get(index).copied(),)*
+                    Self::Custom(_, value) => [value.to_number()].get(index).copied(),
+                }
+            }
+
+            /// Creates the value of a data type from the values of its fields, e.g., the x, y and z axis of an
+            /// accelerometer. Returns `None` if the number of values does not match the fields of the data type or
+            /// if a value is not representable by the type of its field (e.g., 1.5 for a digital input).
+            pub fn from_field_values(lpp_type: LppType, values: &[f64]) -> Option<Self> {
+                match lpp_type {
+                    $(LppType::$variant => {
+                        let [$($value),+] = values.try_into().ok()?;
+                        Some(Self::$variant($(FieldValue::from_number($value)?),+))
+                    },)*
+                }
+            }
+
+            /// Converts the value into its raw value, using the rounding and the overflow policy. The registry is
+            /// used to look up the resolution of custom types.
+            pub(crate) fn to_raw(self, registry: &CayenneLPPRegistry, rounding: Rounding, overflow: Overflow)
+                -> Result<(CayenneLPPRawValue, Encoded), Error> {
+                match self {
+                    $(Self::$variant($($value),+) => {
+                        let ([$($value),+], encoded) = to_fields([$($value),+], LppType::$variant, rounding, overflow)?;
+                        Ok((CayenneLPPRawValue::$variant($(RawField::from_raw($value)),+), encoded))
+                    },)*
+                    Self::Custom(type_code, value) => {
+                        let custom_type = registry.get(type_code).ok_or(Error::UnhandledType(type_code))?;
+                        let factor = 1.0 / custom_type.resolution();
+                        let (min, max) = custom_type.raw_range();
+                        let (raw, encoded) = overflow.scale(value, factor, rounding, min, max)?;
+                        Ok((CayenneLPPRawValue::Custom(type_code, raw), encoded))
+                    },
+                }
+            }
+        }
+
+        impl CayenneLPPRawValue {
+            /// Returns the data type of the raw value, or `None` for a custom type.
+            pub const fn kind(&self) -> Option<LppType> {
+                match self {
+                    $(Self::$variant(..) => Some(LppType::$variant),)*
+                    Self::Custom(..) => None,
+                }
+            }
+
+            /// Returns the type code of the raw value in the payload.
+            pub(crate) const fn type_code(&self) -> u8 {
+                match self {
+                    $(Self::$variant(..) => LppType::$variant.code(),)*
+                    Self::Custom(type_code, _) => *type_code,
+                }
+            }
+
+            /// Returns the raw values of the fields of the data type. Custom types only have a single field.
+            pub(crate) fn to_fields(self) -> [i64; MAX_FIELDS] {
+                match self {
+                    $(Self::$variant($($value),+) => pad([$($value.into_raw()),+]),)*
+                    Self::Custom(_, raw) => pad([raw]),
+                }
+            }
+
+            /// Reads the raw value of a data type from the bytes after its header and returns it together with the
+            /// number of read bytes. The value is `None` if one of its checked fields is out of range (see
+            /// [`LppField::checked`]). Returns `None` if the bytes are shorter than the payload of the data type.
+            #[inline(always)]
+            pub(crate) fn from_bytes(lpp_type: LppType, bytes: &[u8]) -> Option<(Option<Self>, usize)> {
+                match lpp_type {
+                    $(LppType::$variant => {
+                        const FIELDS: &[LppField] = LppType::$variant.info().fields;
+                        const SIZE: usize = LppType::$variant.size() - 2;
+                        let value = read_fields(bytes.first_chunk::<SIZE>()?, FIELDS)
+                            .map(|[$($value),+]| Self::$variant($(RawField::from_raw($value)),+));
+                        Some((value, SIZE))
+                    },)*
+                }
+            }
+
+            /// Converts the raw value to its physical value. The registry is used to look up the resolution of
+            /// custom types.
+            pub(crate) fn to_value(self, registry: &CayenneLPPRegistry) -> CayenneLPPValue {
+                match self {
+                    $(Self::$variant($($value),+) => {
+                        let [$($value),+] = from_fields([$($value.into_raw()),+], LppType::$variant);
+                        CayenneLPPValue::$variant($($value),+)
+                    },)*
+                    Self::Custom(type_code, raw) => {
+                        let resolution = registry.get(type_code).map_or(1.0, |custom_type| custom_type.resolution());
+                        CayenneLPPValue::Custom(type_code, raw as f32 * resolution)
+                    },
+                }
+            }
+        }
+    };
+}
+
+lpp_values! {
+    DigitalInput(value),
+    DigitalOutput(value),
+    AnalogInput(value),
+    AnalogOutput(value),
+    GenericSensor(value),
+    Luminosity(value),
+    Presence(value),
+    Temperature(value),
+    RelativeHumidity(value),
+    Accelerometer(x, y, z),
+    BarometricPressure(value),
+    Voltage(value),
+    Current(value),
+    Frequency(value),
+    Percentage(value),
+    Altitude(value),
+    Concentration(value),
+    Power(value),
+    Distance(value),
+    Energy(value),
+    Direction(value),
+    UnixTime(value),
+    Gyrometer(x, y, z),
+    Color(r, g, b),
+    GPS(latitude, longitude, altitude),
+    Switch(value),
+}
+
+
+/// Single raw value parsed from a CayenneLPP data structure,
+/// including the enumeration of its raw value and it's channel.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct CayenneLPPRawScalar {
+    /// The channel value parsed from a data structure
+    pub channel: u8,
+
+    /// Raw value parsed from a data structure
+    pub value: CayenneLPPRawValue
+}
@@ -0,0 +1,295 @@
+use core::fmt;
+
+use serde::de::{self, DeserializeSeed, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor};
+use serde::ser::{SerializeStructVariant, Serializer};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::cayenne_lpp_scalar::{CayenneLPPValue, FieldNumber, FieldValue};
+use crate::lpp_type::{LPP_TYPES, LppType, MAX_FIELDS};
+
+// The serialized representation of a `CayenneLPPValue` is derived from `LPP_TYPES`: the value is tagged with the name
+// of its data type, e.g. `{"temperature": 21.5}`, and data types with several values are written as a struct with the
+// names of their fields, e.g. `{"accelerometer": {"x": 1.0, "y": -1.0, "z": 0.5}}`. Only the fields of GPS positions
+// are abbreviated: `{"gps": {"lat": 42.3519, "lon": -87.9094, "alt": 10.0}}`. Custom types are written as
+// `{"custom": {"type_code": 200, "value": 1.5}}`. The names are part of the public API and must not be changed.
+
+/// Name of the enum in the serialized representation
+const NAME: &str = "CayenneLPPValue";
+
+/// Tag of custom types, whose variant index follows the data types of `LPP_TYPES`
+const CUSTOM: &str = "custom";
+
+/// Fields of custom types
+const CUSTOM_FIELDS: &[&str] = &["type_code", "value"];
+
+/// Kinds of number of the fields of custom types (see `field_kinds()`)
+const CUSTOM_KINDS: [FieldNumber; MAX_FIELDS] =
+    [FieldNumber::Integer(0), FieldNumber::Float(0.0), FieldNumber::Integer(0)];
+
+/// Tags of all variants in the order of their variant index
+static VARIANTS: [&str; LPP_TYPES.len() + 1] = {
+    let mut variants = [CUSTOM; LPP_TYPES.len() + 1];
+    let mut index = 0;
+    while index < LPP_TYPES.len() {
+        variants[index] = LPP_TYPES[index].name;
+        index += 1;
+    }
+    variants
+};
+
+/// Serialized names of the fields of GPS positions, which differ from their names in `LPP_TYPES`
+const GPS_FIELDS: [&str; MAX_FIELDS] = ["lat", "lon", "alt"];
+
+/// Serialized names of the fields of every data type, which are only used for data types with several fields
+static FIELD_NAMES: [[&str; MAX_FIELDS]; LPP_TYPES.len()] = {
+    let mut names = [[""; MAX_FIELDS]; LPP_TYPES.len()];
+    let mut index = 0;
+    while index < LPP_TYPES.len() {
+        let fields = LPP_TYPES[index].fields;
+        let mut field = 0;
+        while field < fields.len() {
+            names[index][field] = match LPP_TYPES[index].lpp_type {
+                LppType::GPS => GPS_FIELDS[field],
+                _ => fields[field].name,
+            };
+            field += 1;
+        }
+        index += 1;
+    }
+    names
+};
+
+impl Serialize for FieldNumber {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match *self {
+            FieldNumber::Bool(value) => serializer.serialize_bool(value),
+            FieldNumber::Integer(value) => serializer.serialize_i64(value),
+            FieldNumber::Float(value) => serializer.serialize_f32(value),
+        }
+    }
+}
+
+impl Serialize for CayenneLPPValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let number = |index| self.field_number(index).expect("every data type has a value for each of its fields");
+
+        let Some(lpp_type) = self.kind() else {
+            let CayenneLPPValue::Custom(type_code, _) = *self else { unreachable!() };
+            let mut variant = serializer.serialize_struct_variant(NAME, LPP_TYPES.len() as u32, CUSTOM, 2)?;
+            variant.serialize_field(CUSTOM_FIELDS[0], &FieldNumber::Integer(type_code.into()))?;
+            variant.serialize_field(CUSTOM_FIELDS[1], &number(0))?;
+            return variant.end()
+        };
+
+        let index = lpp_type.index();
+        match lpp_type.info().fields.len() {
+            1 => serializer.serialize_newtype_variant(NAME, index as u32, lpp_type.name(), &number(0)),
+            len => {
+                let mut variant = serializer.serialize_struct_variant(NAME, index as u32, lpp_type.name(), len)?;
+                for (field_index, name) in FIELD_NAMES[index][..len].iter().enumerate() {
+                    variant.serialize_field(name, &number(field_index))?;
+                }
+                variant.end()
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for CayenneLPPValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_enum(NAME, &VARIANTS, ValueVisitor)
+    }
+}
+
+/// Deserializes the index of a variant from its tag.
+struct VariantIndex(usize);
+
+impl<'de> Deserialize<'de> for VariantIndex {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_identifier(VariantIndexVisitor)
+    }
+}
+
+struct VariantIndexVisitor;
+
+impl Visitor<'_> for VariantIndexVisitor {
+    type Value = VariantIndex;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("the name of a data type")
+    }
+
+    fn visit_u64<E: de::Error>(self, index: u64) -> Result<Self::Value, E> {
+        match usize::try_from(index) {
+            Ok(index) if index < VARIANTS.len() => Ok(VariantIndex(index)),
+            _ => Err(E::invalid_value(de::Unexpected::Unsigned(index), &self)),
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, name: &str) -> Result<Self::Value, E> {
+        VARIANTS.iter().position(|variant| *variant == name)
+            .map(VariantIndex)
+            .ok_or_else(|| E::unknown_variant(name, &VARIANTS))
+    }
+}
+
+/// Returns a number of every field of the data type, whose kind selects how the field is deserialized. Formats that
+/// are not self-describing, e.g. postcard, can only read the kind that has been written by `Serialize`.
+fn field_kinds(lpp_type: LppType) -> [FieldNumber; MAX_FIELDS] {
+    let zeros = [0.0; MAX_FIELDS];
+    let value = CayenneLPPValue::from_field_values(lpp_type, &zeros[..lpp_type.info().fields.len()])
+        .expect("zero is representable by every field");
+    core::array::from_fn(|index| value.field_number(index).unwrap_or(FieldNumber::Integer(0)))
+}
+
+/// Deserializes a number of the given kind, which is converted into the type of its field by
+/// [`CayenneLPPValue::from_field_values`].
+#[derive(Clone, Copy)]
+struct NumberSeed(FieldNumber);
+
+impl<'de> DeserializeSeed<'de> for NumberSeed {
+    type Value = f64;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        match self.0 {
+            FieldNumber::Bool(_) => deserializer.deserialize_bool(NumberVisitor),
+            FieldNumber::Integer(_) => deserializer.deserialize_i64(NumberVisitor),
+            FieldNumber::Float(_) => deserializer.deserialize_f32(NumberVisitor),
+        }
+    }
+}
+
+struct NumberVisitor;
+
+impl Visitor<'_> for NumberVisitor {
+    type Value = f64;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a number or a boolean")
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<Self::Value, E> {
+        Ok(value.into())
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+        Ok(value as f64)
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+        Ok(value as f64)
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Self::Value, E> {
+        Ok(value)
+    }
+}
+
+/// Deserializes the index of a field from its name.
+struct FieldIndex {
+    names: &'static [&'static str],
+}
+
+impl<'de> DeserializeSeed<'de> for FieldIndex {
+    type Value = usize;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_identifier(self)
+    }
+}
+
+impl Visitor<'_> for FieldIndex {
+    type Value = usize;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "one of the fields {:?}", self.names)
+    }
+
+    fn visit_u64<E: de::Error>(self, index: u64) -> Result<Self::Value, E> {
+        match usize::try_from(index) {
+            Ok(index) if index < self.names.len() => Ok(index),
+            _ => Err(E::invalid_value(de::Unexpected::Unsigned(index), &self)),
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, name: &str) -> Result<Self::Value, E> {
+        self.names.iter().position(|field| *field == name).ok_or_else(|| E::unknown_field(name, self.names))
+    }
+}
+
+/// Deserializes the fields of a struct variant with the given names and kinds of number in any order.
+struct FieldsVisitor {
+    names: &'static [&'static str],
+    kinds: [FieldNumber; MAX_FIELDS],
+}
+
+impl<'de> Visitor<'de> for FieldsVisitor {
+    type Value = [f64; MAX_FIELDS];
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "the fields {:?}", self.names)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut values = [0.0; MAX_FIELDS];
+        for (index, value) in values.iter_mut().enumerate().take(self.names.len()) {
+            *value = seq.next_element_seed(NumberSeed(self.kinds[index]))?
+                .ok_or_else(|| de::Error::invalid_length(index, &self))?;
+        }
+
+        Ok(values)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut values = [None; MAX_FIELDS];
+        while let Some(index) = map.next_key_seed(FieldIndex { names: self.names })? {
+            if values[index].is_some() {
+                return Err(de::Error::custom(format_args!("duplicate field `{}`", self.names[index])))
+            }
+            values[index] = Some(map.next_value_seed(NumberSeed(self.kinds[index]))?);
+        }
+
+        let mut numbers = [0.0; MAX_FIELDS];
+        for (index, name) in self.names.iter().enumerate() {
+            numbers[index] = values[index].ok_or_else(|| de::Error::missing_field(name))?;
+        }
+
+        Ok(numbers)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = CayenneLPPValue;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a value tagged with the name of its data type")
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        let (VariantIndex(index), variant) = data.variant()?;
+
+        let Some(info) = LPP_TYPES.get(index) else {
+            let fields = FieldsVisitor { names: CUSTOM_FIELDS, kinds: CUSTOM_KINDS };
+            let [type_code, value, ..] = variant.struct_variant(CUSTOM_FIELDS, fields)?;
+            let type_code = u8::from_number(type_code)
+                .ok_or_else(|| de::Error::custom("the type code is not representable by a byte"))?;
+            return Ok(CayenneLPPValue::Custom(type_code, value as f32))
+        };
+
+        let kinds = field_kinds(info.lpp_type);
+        let mut values = [0.0; MAX_FIELDS];
+        match info.fields.len() {
+            1 => values[0] = variant.newtype_variant_seed(NumberSeed(kinds[0]))?,
+            len => {
+                let names = &FIELD_NAMES[index][..len];
+                values = variant.struct_variant(names, FieldsVisitor { names, kinds })?;
+            }
+        }
+
+        CayenneLPPValue::from_field_values(info.lpp_type, &values[..info.fields.len()])
+            .ok_or_else(|| de::Error::custom(format_args!("the value is not representable by `{}`", info.name)))
+    }
+}
+
@@ -0,0 +1,123 @@
+use crate::error::Error;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "heapless")]
+use heapless::Vec as HeaplessVec;
+
+/// The memory that contains the payload of a [`CayenneLPP`](crate::CayenneLPP) data structure. It is implemented for
+/// borrowed slices (`&mut [u8]`) and owned arrays (`[u8; N]`), so the data structure can either write into an external
+/// buffer or embed its own buffer (see [`CayenneLPPBuf`](crate::CayenneLPPBuf)). With the `alloc` feature, it is also
+/// implemented for `Vec<u8>`, which grows with the payload, and with the `heapless` feature for `heapless::Vec<u8, N>`,
+/// which grows up to its capacity.
+///
+/// Code that adds values to the payload can be written once for all storages:
+///
+/// ```
+/// use cayenne_lpp::{CayenneLPP, CayenneLPPStorage, Encoded};
+/// use cayenne_lpp::error::Error;
+///
+/// fn report<S: CayenneLPPStorage>(lpp: &mut CayenneLPP<'_, S>) -> Result<Encoded, Error> {
+///     lpp.add_temperature(1, 21.5)
+/// }
+///
+/// let mut buffer = [0u8; 4];
+/// report(&mut CayenneLPP::new(&mut buffer)).unwrap();
+/// report(&mut CayenneLPP::from_storage([0u8; 4])).unwrap();
+/// ```
+///
+/// The trait can also be implemented for other storages. Storages that can grow should override `reserve()` and
+/// `truncate()`.
+pub trait CayenneLPPStorage {
+    /// Returns all bytes of the storage. The payload is located at the beginning of the returned slice.
+    fn as_bytes(&self) -> &[u8];
+
+    /// Returns all bytes of the storage for writing.
+    fn as_bytes_mut(&mut self) -> &mut [u8];
+
+    /// Makes sure that the storage contains at least `len` bytes before a value is written to it.
+    ///
+    /// The default implementation returns `Error::InsufficientMemory` if the storage is smaller than `len` bytes.
+    fn reserve(&mut self, len: usize) -> Result<(), Error> {
+        if len > self.as_bytes().len() {
+            return Err(Error::InsufficientMemory);
+        }
+
+        Ok(())
+    }
+
+    /// Shrinks the storage to `len` bytes after the payload has been shortened, e.g., by `reset()`, `rollback()` or
+    /// a value that could not be written after `reserve()`.
+    ///
+    /// The default implementation does nothing, since the size of a fixed storage does not change.
+    fn truncate(&mut self, _len: usize) {}
+}
+
+impl CayenneLPPStorage for &mut [u8] {
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+
+    fn as_bytes_mut(&mut self) -> &mut [u8] {
+        self
+    }
+}
+
+impl<const N: usize> CayenneLPPStorage for [u8; N] {
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+
+    fn as_bytes_mut(&mut self) -> &mut [u8] {
+        self
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl CayenneLPPStorage for Vec<u8> {
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+
+    fn as_bytes_mut(&mut self) -> &mut [u8] {
+        self
+    }
+
+    /// Grows the vector to `len` bytes, so `Error::InsufficientMemory` is never returned.
+    fn reserve(&mut self, len: usize) -> Result<(), Error> {
+        if len > self.len() {
+            self.resize(len, 0);
+        }
+
+        Ok(())
+    }
+
+    /// Shrinks the vector to `len` bytes, so it only contains the payload.
+    fn truncate(&mut self, len: usize) {
+        Vec::truncate(self, len);
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<const N: usize> CayenneLPPStorage for HeaplessVec<u8, N> {
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+
+    fn as_bytes_mut(&mut self) -> &mut [u8] {
+        self
+    }
+
+    /// Grows the vector to `len` bytes or returns `Error::InsufficientMemory` if this exceeds its capacity.
+    fn reserve(&mut self, len: usize) -> Result<(), Error> {
+        if len > self.len() {
+            self.resize(len, 0).map_err(|_| Error::InsufficientMemory)?;
+        }
+
+        Ok(())
+    }
+
+    /// Shrinks the vector to `len` bytes, so it only contains the payload.
+    fn truncate(&mut self, len: usize) {
+        HeaplessVec::truncate(self, len);
+    }
+}
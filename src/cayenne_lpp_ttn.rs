@@ -0,0 +1,171 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use serde_json::{Map, Number, Value};
+
+use crate::cayenne_lpp_decoder::CayenneLPPDecoder;
+use crate::cayenne_lpp_scalar::{CayenneLPPRawScalar, CayenneLPPScalar, CayenneLPPValue};
+use crate::cayenne_lpp_storage::CayenneLPPStorage;
+use crate::error::{DecodeError, DecodeErrorKind, Error, JsonError, JsonErrorKind};
+use crate::lpp_type::{LppField, LppType, MAX_FIELDS};
+use crate::overflow::Encoded;
+use crate::{CayenneLPP, CayenneLPPVec};
+
+/// Returns the name of a data type in the keys of the JSON object. The data types of the original Cayenne LPP API
+/// use the names of the payload formatter of The Things Network, the additional data types, which are not
+/// recognized by the payload formatter, use their names in [`LPP_TYPES`](crate::LPP_TYPES).
+fn ttn_name(lpp_type: LppType) -> &'static str {
+    match lpp_type {
+        LppType::DigitalInput => "digital_in",
+        LppType::DigitalOutput => "digital_out",
+        LppType::AnalogInput => "analog_in",
+        LppType::AnalogOutput => "analog_out",
+        _ => lpp_type.name(),
+    }
+}
+
+/// Converts the raw value of a field into a JSON number. The number is calculated from the raw value, so it is the
+/// shortest decimal number of the payload resolution (e.g., `27.2` instead of the `f32` value `27.200000762939453`).
+/// Like the payload formatter of The Things Network, integral numbers are written without a fraction.
+fn ttn_number(raw: i64, field: &LppField) -> Value {
+    let number = raw as f64 / field.factor as f64;
+    if number as i64 as f64 == number {
+        return Value::from(number as i64)
+    }
+
+    Number::from_f64(number).map_or(Value::Null, Value::Number)
+}
+
+/// Converts a raw scalar into its key and value in the JSON object, e.g., `"temperature_5": 21.5` or
+/// `"gps_1": {"latitude": 42.3519, "longitude": -87.9094, "altitude": 10}`.
+fn ttn_entry(scalar: CayenneLPPRawScalar) -> Result<(String, Value), Error> {
+    let Some(lpp_type) = scalar.value.kind() else {
+        return Err(Error::UnhandledType(scalar.value.type_code()))
+    };
+
+    let fields = lpp_type.info().fields;
+    let raw = scalar.value.to_fields();
+    let value = match fields {
+        [field] => ttn_number(raw[0], field),
+        _ => Value::Object(fields.iter().zip(raw)
+            .map(|(field, raw)| (field.name.to_string(), ttn_number(raw, field)))
+            .collect()),
+    };
+
+    Ok((format!("{}_{}", ttn_name(lpp_type), scalar.channel), value))
+}
+
+/// Creates the error for an entry of the JSON object that could not be converted.
+fn json_error(key: &str, kind: JsonErrorKind) -> Error {
+    Error::Json(JsonError { key: key.to_string(), kind })
+}
+
+/// Converts an entry of the JSON object into a scalar.
+fn ttn_scalar(key: &str, value: &Value) -> Result<CayenneLPPScalar, Error> {
+    let error = |kind| json_error(key, kind);
+
+    let Some((lpp_type, suffix)) = LppType::ALL.iter().find_map(|&lpp_type| {
+        let suffix = key.strip_prefix(ttn_name(lpp_type))?;
+        (suffix.is_empty() || suffix.starts_with('_')).then_some((lpp_type, suffix))
+    }) else {
+        return Err(error(JsonErrorKind::UnknownKey))
+    };
+
+    let channel = suffix.strip_prefix('_')
+        .filter(|channel| !channel.is_empty() && channel.bytes().all(|byte| byte.is_ascii_digit()))
+        .and_then(|channel| channel.parse().ok())
+        .ok_or_else(|| error(JsonErrorKind::InvalidChannel))?;
+
+    let fields = lpp_type.info().fields;
+    let mut numbers = [0.0; MAX_FIELDS];
+    match (fields, value) {
+        ([_], value) => {
+            numbers[0] = value.as_f64().ok_or_else(|| error(JsonErrorKind::InvalidValue))?;
+        },
+        (fields, Value::Object(object)) if object.len() == fields.len() => {
+            for (number, field) in numbers.iter_mut().zip(fields) {
+                *number = object.get(field.name).and_then(Value::as_f64)
+                    .ok_or_else(|| error(JsonErrorKind::InvalidValue))?;
+            }
+        },
+        _ => return Err(error(JsonErrorKind::InvalidValue)),
+    }
+
+    let value = CayenneLPPValue::from_field_values(lpp_type, &numbers[..fields.len()])
+        .ok_or_else(|| error(JsonErrorKind::OutOfRange))?;
+    Ok(CayenneLPPScalar { channel, value })
+}
+
+impl<'a> CayenneLPPDecoder<'a> {
+    /// Decodes the payload into a JSON object in the format of the `decoded_payload` of the Cayenne LPP payload
+    /// formatter of The Things Network, e.g., `{"temperature_5": 21.5, "digital_in_3": 85}`. Entries with the same
+    /// data type and channel overwrite each other like in the payload formatter. The first error in the payload is
+    /// returned, custom types cannot be written and return `Error::UnhandledType`.
+    pub fn decode_to_ttn_json(&self) -> Result<Map<String, Value>, Error> {
+        let mut object = Map::new();
+
+        for scalar in self.raw_iter() {
+            let scalar = match scalar {
+                Ok(scalar) => scalar,
+                Err(Error::Decode(DecodeError { kind: DecodeErrorKind::SkippedType { .. }, .. })) => continue,
+                Err(error) => return Err(error),
+            };
+
+            let (key, value) = ttn_entry(scalar)?;
+            object.insert(key, value);
+        }
+
+        Ok(object)
+    }
+}
+
+/// Decodes the payload into a JSON object in the format of the Cayenne LPP payload formatter of The Things Network.
+/// Use [`CayenneLPPDecoder::decode_to_ttn_json`] to skip extra types.
+pub fn decode_to_ttn_json(buffer: &[u8]) -> Result<Map<String, Value>, Error> {
+    CayenneLPPDecoder::new(buffer).decode_to_ttn_json()
+}
+
+/// Converts a JSON object in the format of the Cayenne LPP payload formatter of The Things Network back into
+/// scalars. The scalars are sorted like the keys of the object. An entry that cannot be converted is returned as
+/// `Error::Json`.
+pub fn scalars_from_ttn_json(object: &Map<String, Value>) -> Result<Vec<CayenneLPPScalar>, Error> {
+    object.iter().map(|(key, value)| ttn_scalar(key, value)).collect()
+}
+
+impl<'a, S: CayenneLPPStorage> CayenneLPP<'a, S> {
+    /// Adds all entries of a JSON object in the format of the payload formatter of The Things Network, e.g.,
+    /// `{"digital_out_5": 1, "analog_out_3": 12.5}`. The entries are added in the order of their keys with the
+    /// `add_*()` functions, using the rounding and the overflow policy of the data structure. Like `add_batch()`,
+    /// the first error is returned and the payload stays unchanged. Values that are not representable by their
+    /// data type are returned as `Error::Json` with the kind `JsonErrorKind::OutOfRange`.
+    pub fn add_ttn_json(&mut self, object: &Map<String, Value>) -> Result<Encoded, Error> {
+        let checkpoint = self.checkpoint();
+        let mut encoded = Encoded::Exact;
+
+        for (key, value) in object {
+            let result = ttn_scalar(key, value).and_then(|scalar| match self.add_scalar(&scalar) {
+                Err(Error::OutOfRange) => Err(json_error(key, JsonErrorKind::OutOfRange)),
+                result => result,
+            });
+
+            match result {
+                Ok(result) => encoded = encoded.and(result),
+                Err(error) => {
+                    self.rollback(checkpoint);
+                    return Err(error);
+                }
+            }
+        }
+
+        Ok(encoded)
+    }
+}
+
+/// Encodes a JSON object in the format of the payload formatter of The Things Network, e.g., a downlink like
+/// `{"digital_out_5": 1, "analog_out_3": 12.5}`, into a payload. See [`CayenneLPP::add_ttn_json`].
+pub fn encode_ttn_json(object: &Map<String, Value>) -> Result<Vec<u8>, Error> {
+    let mut lpp = CayenneLPPVec::default();
+    lpp.add_ttn_json(object)?;
+
+    Ok(lpp.payload_slice().to_vec())
+}
@@ -1,156 +1,185 @@
+use crate::lpp_type::LppType;
+
 /// Data type of a digital input
-pub const LPP_DIGITAL_INPUT: u8 =       0;       // 1 byte
+pub const LPP_DIGITAL_INPUT: u8 = LppType::DigitalInput.code();
 
 /// Data type of a digital output
-pub const LPP_DIGITAL_OUTPUT: u8 =      1;       // 1 byte
+pub const LPP_DIGITAL_OUTPUT: u8 = LppType::DigitalOutput.code();
 
 /// Data type of an analog input
-pub const LPP_ANALOG_INPUT: u8 =        2;       // 2 bytes, 0.01 signed
+pub const LPP_ANALOG_INPUT: u8 = LppType::AnalogInput.code();
 
 /// Data type of an analog output
-pub const LPP_ANALOG_OUTPUT: u8 =       3;       // 2 bytes, 0.01 signed
+pub const LPP_ANALOG_OUTPUT: u8 = LppType::AnalogOutput.code();
 
 /// Data type of a generic sensor
-pub const LPP_GENERIC_SENSOR: u8 =      100;     // 4 bytes, unsigned
+pub const LPP_GENERIC_SENSOR: u8 = LppType::GenericSensor.code();
 
 /// Data type of a luminosity value
-pub const LPP_LUMINOSITY: u8 =          101;     // 2 bytes, 1 lux unsigned
+pub const LPP_LUMINOSITY: u8 = LppType::Luminosity.code();
 
 /// Data type of a presence sensor
-pub const LPP_PRESENCE: u8 =            102;     // 1 byte, 1
+pub const LPP_PRESENCE: u8 = LppType::Presence.code();
 
 /// Data type of a temperature value
-pub const LPP_TEMPERATURE: u8 =         103;     // 2 bytes, 0.1°C signed
+pub const LPP_TEMPERATURE: u8 = LppType::Temperature.code();
 
 /// Data type of a relative humidity value
-pub const LPP_RELATIVE_HUMIDITY: u8 =   104;     // 1 byte, 0.5% unsigned
+pub const LPP_RELATIVE_HUMIDITY: u8 = LppType::RelativeHumidity.code();
 
 /// Data type of accelerometer values
-pub const LPP_ACCELEROMETER: u8 =       113;     // 2 bytes per axis, 0.001G
+pub const LPP_ACCELEROMETER: u8 = LppType::Accelerometer.code();
 
 /// Data type of a barometric pressure value
-pub const LPP_BAROMETRIC_PRESSURE: u8 = 115;     // 2 bytes 0.1 hPa Unsigned
+pub const LPP_BAROMETRIC_PRESSURE: u8 = LppType::BarometricPressure.code();
 
 /// Data type of a voltage value
-pub const LPP_VOLTAGE: u8 =             116;     // 2 bytes 0.01V unsigned
+pub const LPP_VOLTAGE: u8 = LppType::Voltage.code();
 
 /// Data type of a current value
-pub const LPP_CURRENT: u8 =             117;     // 2 bytes 1mA unsigned
+pub const LPP_CURRENT: u8 = LppType::Current.code();
 
 /// Data type of a frequency value
-pub const LPP_FREQUENCY: u8 =           118;     // 4 bytes 1hz unsigned
+pub const LPP_FREQUENCY: u8 = LppType::Frequency.code();
 
 /// Data type of a percentage
-pub const LPP_PERCENTAGE: u8 =          120;     // 1 byte 1-100% unsigned
+pub const LPP_PERCENTAGE: u8 = LppType::Percentage.code();
 
 /// Data type of an altitude
-pub const LPP_ALTITUDE: u8 =            121;     // 2 byte 1m signed
+pub const LPP_ALTITUDE: u8 = LppType::Altitude.code();
 
 /// Data type of a concentration
-pub const LPP_CONCENTRATION: u8 =       125;     // 2 bytes, 1ppm unsigned
+pub const LPP_CONCENTRATION: u8 = LppType::Concentration.code();
 
 /// Data type of a power value
-pub const LPP_POWER: u8 =               128;     // 2 bytes 1W unsigned
+pub const LPP_POWER: u8 = LppType::Power.code();
 
 /// Data type of a distance value
-pub const LPP_DISTANCE: u8 =            130;     // 4 bytes, 0.001m unsigned
+pub const LPP_DISTANCE: u8 = LppType::Distance.code();
 
 /// Data type of an energy value
-pub const LPP_ENERGY: u8 =              131;     // 4 bytes, 1Wh, unsigned
+pub const LPP_ENERGY: u8 = LppType::Energy.code();
 
 /// Data type of a direction value
-pub const LPP_DIRECTION: u8 =           132;     // 2 bytes, 1 deg, unsigned
+pub const LPP_DIRECTION: u8 = LppType::Direction.code();
 
 /// Data type of a time (unix timestamp)
-pub const LPP_UNIXTIME: u8 =            133;     // 4 bytes, unsigned
+pub const LPP_UNIXTIME: u8 = LppType::UnixTime.code();
 
 /// Data type of gyrometer values
-pub const LPP_GYROMETER: u8 =           134;     // 2 bytes per axis, 0.01 °/s
+pub const LPP_GYROMETER: u8 = LppType::Gyrometer.code();
 
 /// Data type of a color value
-pub const LPP_COLOR: u8 =               135;     // 1 byte per RGB color
+pub const LPP_COLOR: u8 = LppType::Color.code();
 
 /// Data type of GPS value
-pub const LPP_GPS: u8 =                 136;     // 3 byte lon/lat 0.0001 °, 3 bytes alt 0.01 meter
+pub const LPP_GPS: u8 = LppType::GPS.code();
 
 /// Data type of switch value
-pub const LPP_SWITCH: u8 =              142;     // 1 byte 0/1
+pub const LPP_SWITCH: u8 = LppType::Switch.code();
 
 // Data ID + Data Type + Data Size
 /// Size of a digital input packet including channel and data type
-pub const LPP_DIGITAL_INPUT_SIZE: usize =       3;       // 1 byte
+pub const LPP_DIGITAL_INPUT_SIZE: usize = LppType::DigitalInput.size();
 
 /// Size of a digital output packet including channel and data type
-pub const LPP_DIGITAL_OUTPUT_SIZE: usize =      3;       // 1 byte
+pub const LPP_DIGITAL_OUTPUT_SIZE: usize = LppType::DigitalOutput.size();
 
 /// Size of an analog input packet including channel and data type
-pub const LPP_ANALOG_INPUT_SIZE: usize =        4;       // 2 bytes, 0.01 signed
+pub const LPP_ANALOG_INPUT_SIZE: usize = LppType::AnalogInput.size();
 
 /// Size of an analog output packet including channel and data type
-pub const LPP_ANALOG_OUTPUT_SIZE: usize =       4;       // 2 bytes, 0.01 signed
+pub const LPP_ANALOG_OUTPUT_SIZE: usize = LppType::AnalogOutput.size();
 
 /// Size of a generic sensor packet including channel and data type
-pub const LPP_GENERIC_SENSOR_SIZE: usize =      6;       // 4 bytes, unsigned
+pub const LPP_GENERIC_SENSOR_SIZE: usize = LppType::GenericSensor.size();
 
 /// Size of a luminosity packet including channel and data type
-pub const LPP_LUMINOSITY_SIZE: usize =          4;       // 2 bytes, 1 lux unsigned
+pub const LPP_LUMINOSITY_SIZE: usize = LppType::Luminosity.size();
 
 /// Size of a presence sensor packet including channel and data type
-pub const LPP_PRESENCE_SIZE: usize =            3;       // 1 byte, 1
+pub const LPP_PRESENCE_SIZE: usize = LppType::Presence.size();
 
 /// Size of a temperature packet including channel and data type
-pub const LPP_TEMPERATURE_SIZE: usize =         4;       // 2 bytes, 0.1°C signed
+pub const LPP_TEMPERATURE_SIZE: usize = LppType::Temperature.size();
 
 /// Size of a relative humidity packet including channel and data type
-pub const LPP_RELATIVE_HUMIDITY_SIZE: usize =   3;       // 1 byte, 0.5% unsigned
+pub const LPP_RELATIVE_HUMIDITY_SIZE: usize = LppType::RelativeHumidity.size();
 
 /// Size of an accelerometer packet including channel and data type
-pub const LPP_ACCELEROMETER_SIZE: usize =       8;       // 2 bytes per axis, 0.001G
+pub const LPP_ACCELEROMETER_SIZE: usize = LppType::Accelerometer.size();
 
 /// Size of a barometric pressure packet including channel and data type
-pub const LPP_BAROMETRIC_PRESSURE_SIZE: usize = 4;       // 2 bytes 0.1 hPa unsigned
+pub const LPP_BAROMETRIC_PRESSURE_SIZE: usize = LppType::BarometricPressure.size();
 
 /// Size of a voltage packet including channel and data type
-pub const LPP_VOLTAGE_SIZE: usize =             4;       // 2 bytes 0.01V unsigned
+pub const LPP_VOLTAGE_SIZE: usize = LppType::Voltage.size();
 
 /// Size of a current packet including channel and data type
-pub const LPP_CURRENT_SIZE: usize =             4;      // 2 bytes 1mA unsigned
+pub const LPP_CURRENT_SIZE: usize = LppType::Current.size();
 
 /// Size of a frequency packet including channel and data type
-pub const LPP_FREQUENCY_SIZE: usize =           6;      // 4 bytes, 1hz unsigned
+pub const LPP_FREQUENCY_SIZE: usize = LppType::Frequency.size();
 
 /// Size of an percentage packet including channel and data type
-pub const LPP_PERCENTAGE_SIZE: usize =          3;      // 1 bytes, 0-100% unsigned
+pub const LPP_PERCENTAGE_SIZE: usize = LppType::Percentage.size();
 
 /// Size of an altitude packet including channel and data type
-pub const LPP_ALTITUDE_SIZE: usize =            4;      // 2 bytes, 1M signed
+pub const LPP_ALTITUDE_SIZE: usize = LppType::Altitude.size();
 
 /// Size of a concentration packet including channel and data type
-pub const LPP_CONCENTRATION_SIZE: usize =       4;      // 2 bytes, 1ppm unsigned
+pub const LPP_CONCENTRATION_SIZE: usize = LppType::Concentration.size();
 
 /// Size of a power packet including channel and data type
-pub const LPP_POWER_SIZE: usize =               4;      // 2 bytes, 1W unsigned
+pub const LPP_POWER_SIZE: usize = LppType::Power.size();
 
 /// Size of a distance packet including channel and data type
-pub const LPP_DISTANCE_SIZE: usize =            6;      // 4 bytes, 1mm unsigned
+pub const LPP_DISTANCE_SIZE: usize = LppType::Distance.size();
 
 /// Size of an energy packet including channel and data type
-pub const LPP_ENERGY_SIZE: usize =              6;      // 4 bytes, 1Wh unsigned
+pub const LPP_ENERGY_SIZE: usize = LppType::Energy.size();
 
 /// Size of a direction packet including channel and data type
-pub const LPP_DIRECTION_SIZE: usize =           4;      // 2 bytes, 1deg, unsigned
+pub const LPP_DIRECTION_SIZE: usize = LppType::Direction.size();
 
 /// Size of a unix time packet including channel and data type
-pub const LPP_UNIXTIME_SIZE: usize =            6;      // 4 bytes, unsigned
+pub const LPP_UNIXTIME_SIZE: usize = LppType::UnixTime.size();
 
 /// Size of a gyrometer packet including channel and data type
-pub const LPP_GYROMETER_SIZE: usize =           8;       // 2 bytes per axis, 0.01 °/s
+pub const LPP_GYROMETER_SIZE: usize = LppType::Gyrometer.size();
 
 /// Size of a color packet including channel and data type
-pub const LPP_COLOR_SIZE: usize =               5;      // 1 bytes per color, RGB order
+pub const LPP_COLOR_SIZE: usize = LppType::Color.size();
 
 /// Size of a GPS packet including channel and data type
-pub const LPP_GPS_SIZE: usize =                 11;      // 3 byte lon/lat 0.0001 °, 3 bytes alt 0.01 meter
+pub const LPP_GPS_SIZE: usize = LppType::GPS.size();
 
 /// Size of a switch packet including channel and data type
-pub const LPP_SWITCH_SIZE: usize =              3;      // 1 byte 0/1
\ No newline at end of file
+pub const LPP_SWITCH_SIZE: usize = LppType::Switch.size();
+
+/// Returns the size of a packet of the given data type including channel and data type, or `None` if the data type is
+/// not handled by this library (e.g., a custom type). The function can be used in constant expressions.
+pub const fn lpp_size(type_code: u8) -> Option<usize> {
+    match LppType::from_code(type_code) {
+        Some(lpp_type) => Some(lpp_type.size()),
+        None => None
+    }
+}
+
+/// Calculates the size of a payload with the given data types at compile time, so it can be used for the length of
+/// the buffer. The data types are named like the variants of [`LppType`](crate::LppType).
+///
+/// ```
+/// use cayenne_lpp::{lpp_size, CayenneLPP};
+///
+/// let mut buffer = [0u8; lpp_size!(Temperature, RelativeHumidity, GPS)];
+/// let mut lpp = CayenneLPP::new(&mut buffer);
+///
+/// lpp.add_temperature(1, 21.5).unwrap();
+/// lpp.add_relative_humidity(2, 40.0).unwrap();
+/// lpp.add_gps(3, 42.3519, -87.9094, 10.0).unwrap();
+/// ```
+#[macro_export]
+macro_rules! lpp_size {
+    ($($name:ident),* $(,)?) => { 0 $(+ $crate::LppType::$name.size())* };
+}
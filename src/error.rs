@@ -1,5 +1,12 @@
-/// Defines all the errors that can occur in this crate.
+#[cfg(feature = "ttn")]
+use alloc::string::String;
+
+/// Defines all the errors that can occur in this crate. Features and new versions may add variants (e.g., `Json` with
+/// the `ttn` feature), so matches need a wildcard arm.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[non_exhaustive]
 pub enum Error {
     /// The buffer is too small to add the value
     InsufficientMemory,
@@ -11,5 +18,196 @@ pub enum Error {
     /// The provided type code is either invalid or
     /// not handled by this library
     UnhandledType(u8),
-    
-}
\ No newline at end of file
+    /// An entry of a payload could not be decoded. The contained
+    /// error describes where in the payload decoding failed.
+    Decode(DecodeError),
+    /// An entry of a JSON object in the format of The Things Network could not be converted.
+    /// The contained error describes the entry and why it could not be converted.
+    #[cfg(feature = "ttn")]
+    Json(JsonError),
+}
+
+/// Describes an entry of a payload that could not be decoded.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DecodeError {
+    /// Byte offset of the failing entry (i.e., of its channel byte) in the payload
+    pub offset: usize,
+    /// Channel of the failing entry
+    pub channel: u8,
+    /// Type code of the failing entry. This is `None` if the payload
+    /// ended directly after the channel byte.
+    pub type_code: Option<u8>,
+    /// The reason why the entry could not be decoded
+    pub kind: DecodeErrorKind,
+}
+
+/// The reason why an entry of a payload could not be decoded. New versions may add reasons, so matches need a
+/// wildcard arm.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[non_exhaustive]
+pub enum DecodeErrorKind {
+    /// The payload didn't contain enough bytes to unpack the entry
+    BufferUnderrun {
+        /// Number of bytes of the entry, including channel and type code
+        expected: usize,
+        /// Number of bytes that were left in the payload, starting at the entry
+        available: usize,
+    },
+    /// The type code is either invalid or not handled by this library
+    UnhandledType,
+    /// The unpacked value is not representable by CayenneLPP
+    OutOfRange,
+    /// The type code is not handled by this library, but its size was
+    /// registered with the decoder, so the entry was skipped. Decoding
+    /// continues with the next entry.
+    SkippedType {
+        /// Number of bytes of the skipped entry, including channel and type code
+        size: usize,
+    },
+}
+
+/// Describes an entry of a JSON object in the format of The Things Network that could not be converted.
+#[cfg(feature = "ttn")]
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct JsonError {
+    /// Key of the failing entry, e.g., `temperature_5`
+    pub key: String,
+    /// The reason why the entry could not be converted
+    pub kind: JsonErrorKind,
+}
+
+/// The reason why an entry of a JSON object could not be converted.
+#[cfg(feature = "ttn")]
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum JsonErrorKind {
+    /// The name of the data type in the key is not known
+    UnknownKey,
+    /// The key doesn't end with a valid channel, e.g., `_5`
+    InvalidChannel,
+    /// The value is no number, or no object with a number for every field of the data type
+    InvalidValue,
+    /// A number is not representable by the data type
+    OutOfRange,
+}
+
+/// Describes an entry of a downlink that could not be sent to the handler of an actuator.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum DispatchError {
+    /// No handler is registered for the channel and the data type of the command
+    UnknownChannel {
+        /// Channel of the command
+        channel: u8,
+        /// Type code of the command
+        type_code: u8,
+    },
+    /// The data type of the entry does not control an output, e.g., a temperature
+    InputOnlyType {
+        /// Channel of the entry
+        channel: u8,
+        /// Type code of the entry
+        type_code: u8,
+    },
+    /// The entry could not be decoded
+    Decode(DecodeError),
+}
+
+impl From<DecodeError> for Error {
+    fn from(error: DecodeError) -> Self {
+        Error::Decode(error)
+    }
+}
+
+#[cfg(feature = "ttn")]
+impl From<JsonError> for Error {
+    fn from(error: JsonError) -> Self {
+        Error::Json(error)
+    }
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::InsufficientMemory => f.write_str("the buffer is too small to add the value"),
+            Error::OutOfRange => f.write_str("the value is not representable by Cayenne LPP"),
+            Error::BufferUnderrun => f.write_str("the buffer does not contain enough bytes to unpack the next value"),
+            Error::UnhandledType(type_code) => write!(f, "the type code {} is invalid or not handled", type_code),
+            Error::Decode(error) => core::fmt::Display::fmt(error, f),
+            #[cfg(feature = "ttn")]
+            Error::Json(error) => core::fmt::Display::fmt(error, f),
+        }
+    }
+}
+
+impl core::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "failed to decode the entry at byte offset {} (channel {}, ", self.offset, self.channel)?;
+        match self.type_code {
+            Some(type_code) => write!(f, "type code {}", type_code)?,
+            None => f.write_str("missing type code")?,
+        }
+        write!(f, "): {}", self.kind)
+    }
+}
+
+impl core::fmt::Display for DecodeErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DecodeErrorKind::BufferUnderrun { expected, available } => {
+                write!(f, "expected {} bytes, but only {} are available", expected, available)
+            },
+            DecodeErrorKind::UnhandledType => f.write_str("the type code is invalid or not handled"),
+            DecodeErrorKind::OutOfRange => f.write_str("the value is not representable by Cayenne LPP"),
+            DecodeErrorKind::SkippedType { size } => write!(f, "skipped {} bytes of an extra type", size),
+        }
+    }
+}
+
+#[cfg(feature = "ttn")]
+impl core::fmt::Display for JsonError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "failed to convert the entry \"{}\": {}", self.key, self.kind)
+    }
+}
+
+#[cfg(feature = "ttn")]
+impl core::fmt::Display for JsonErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            JsonErrorKind::UnknownKey => f.write_str("the data type is not known"),
+            JsonErrorKind::InvalidChannel => f.write_str("the key does not end with a valid channel"),
+            JsonErrorKind::InvalidValue => f.write_str("the value does not match the fields of the data type"),
+            JsonErrorKind::OutOfRange => f.write_str("the value is not representable by the data type"),
+        }
+    }
+}
+
+impl core::fmt::Display for DispatchError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DispatchError::UnknownChannel { channel, type_code } => {
+                write!(f, "no handler is registered for type code {} on channel {}", type_code, channel)
+            },
+            DispatchError::InputOnlyType { channel, type_code } => {
+                write!(f, "type code {} on channel {} does not control an output", type_code, channel)
+            },
+            DispatchError::Decode(error) => core::fmt::Display::fmt(error, f),
+        }
+    }
+}
+
+impl core::error::Error for Error {}
+
+impl core::error::Error for DecodeError {}
+
+impl core::error::Error for DispatchError {}
+
+#[cfg(feature = "ttn")]
+impl core::error::Error for JsonError {}
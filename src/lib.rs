@@ -11,17 +11,57 @@ trivial_numeric_casts)]
 //!
 //! The original C++ version of [Cayenne LPP] can be found [here].
 //!
+//! The crate works without a memory allocator. The optional `alloc` feature adds a growable encoder
+//! (`CayenneLPPVec`) and `decode_to_vec()`, the `std` feature enables everything of `alloc` on targets with the
+//! standard library. The `heapless` feature allows to use a `heapless::Vec` as storage of the encoder. The `serde`
+//! feature implements `Serialize` and `Deserialize` for `CayenneLPPScalar`, `CayenneLPPValue` and the errors. The
+//! `ttn` feature converts payloads into the JSON objects of the payload formatter of The Things Network and encodes
+//! such objects, e.g., downlinks, into payloads.
+//!
 //! [Cayenne LPP]: https://docs.mydevices.com/docs/lorawan/cayenne-lpp
 //! [here]: https://github.com/myDevicesIoT/CayenneLPP
 
-use crate::cayenne_lpp_into_iterator::CayenneLPPIntoFailableIterator;
-pub use crate::cayenne_lpp_scalar::{CayenneLPPScalar, CayenneLPPValue};
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+pub use crate::cayenne_lpp_builder::CayenneLPPBuilder;
+pub use crate::cayenne_lpp_custom_type::{CayenneLPPCustomType, CayenneLPPRegistry, CayenneLPPScaledType};
+pub use crate::cayenne_lpp_decoder::{CayenneLPPDecoder, CayenneLPPExtraType};
+pub use crate::cayenne_lpp_dispatcher::{CayenneLPPCommand, CayenneLPPDispatcher, CayenneLPPHandler, CayenneLPPRoute};
+#[cfg(feature = "alloc")]
+pub use crate::cayenne_lpp_decoder::decode_to_vec;
+#[cfg(feature = "ttn")]
+pub use crate::cayenne_lpp_ttn::{decode_to_ttn_json, encode_ttn_json, scalars_from_ttn_json};
+pub use crate::cayenne_lpp_into_iterator::{CayenneLPPIntoFailableIterator, CayenneLPPIterator, CayenneLPPRawIterator};
+pub use crate::cayenne_lpp_scalar::{CayenneLPPRawScalar, CayenneLPPRawValue, CayenneLPPScalar, CayenneLPPValue};
+pub use crate::cayenne_lpp_storage::CayenneLPPStorage;
 pub use crate::constants::*;
+pub use crate::lpp_type::{LPP_TYPES, LppField, LppType, LppTypeInfo};
 use crate::error::Error;
+use crate::cayenne_lpp_scalar::{to_fields, FieldValue};
+pub use crate::overflow::{Encoded, Overflow};
+pub use crate::rounding::Rounding;
 
 pub(crate) mod constants;
 pub(crate) mod cayenne_lpp_scalar;
+mod cayenne_lpp_builder;
+mod cayenne_lpp_custom_type;
+mod cayenne_lpp_decoder;
+mod cayenne_lpp_dispatcher;
 mod cayenne_lpp_into_iterator;
+#[cfg(feature = "serde")]
+mod cayenne_lpp_serde;
+mod cayenne_lpp_storage;
+#[cfg(feature = "ttn")]
+mod cayenne_lpp_ttn;
+pub(crate) mod lpp_type;
+pub(crate) mod overflow;
+pub(crate) mod rounding;
 
 /// Errors that may occur in the module
 pub mod error;
@@ -32,11 +72,56 @@ mod tests;
 /// This struct contains the data of the added payload objects and an index that points to the next free
 /// value in the array. All newly added values will increase the index. After adding all the values, the buffer
 /// contains the payloads of the different data types and has a length of ''index''.
-pub struct CayenneLPP<'a> {
-    buffer: &'a mut [u8],
+///
+/// By default, the data structure writes into a borrowed buffer. The storage type `S` can be used to embed the buffer
+/// into the data structure instead (see [`CayenneLPPBuf`]) or to write into a growing vector (see
+/// [`CayenneLPPStorage`]).
+pub struct CayenneLPP<'a, S = &'a mut [u8]> {
+    buffer: S,
+    index: usize,
+    registry: CayenneLPPRegistry<'a>,
+    rounding: Rounding,
+    overflow: Overflow
+}
+
+/// Position in the payload of a [`CayenneLPP`] data structure that is returned by `checkpoint()`. It can be used to
+/// remove all values that were added afterwards with `rollback()`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct CayenneLPPCheckpoint {
     index: usize
 }
 
+/// Cayenne LPP data structure that owns a buffer of `N` bytes. In contrast to [`CayenneLPP`] with a borrowed buffer,
+/// it can be stored in other structs, returned from functions or moved between tasks.
+///
+/// ```
+/// use cayenne_lpp::{CayenneLPPBuf, LPP_TEMPERATURE_SIZE};
+///
+/// fn measure() -> CayenneLPPBuf<LPP_TEMPERATURE_SIZE> {
+///     let mut lpp = CayenneLPPBuf::default();
+///     lpp.add_temperature(3, 21.5).unwrap();
+///     lpp
+/// }
+///
+/// assert_eq!(measure().payload_slice(), &[0x03, 0x67, 0x00, 0xD7]);
+/// ```
+pub type CayenneLPPBuf<const N: usize> = CayenneLPP<'static, [u8; N]>;
+
+/// Cayenne LPP data structure that writes its payload into a `Vec`, which grows with every added value. Hence, it is
+/// not necessary to calculate the size of the buffer and `Error::InsufficientMemory` is never returned.
+///
+/// ```
+/// use cayenne_lpp::CayenneLPPVec;
+///
+/// let mut lpp = CayenneLPPVec::default();
+/// lpp.add_temperature(3, 21.5).unwrap();
+/// lpp.add_digital_input(5, 0x55).unwrap();
+///
+/// assert_eq!(lpp.into_storage(), [0x03, 0x67, 0x00, 0xD7, 0x05, 0x00, 0x55]);
+/// ```
+#[cfg(feature = "alloc")]
+pub type CayenneLPPVec = CayenneLPP<'static, Vec<u8>>;
+
 impl<'a> CayenneLPP<'a> {
 
     /// Creates a new buffer for the Cayenne LPP. Since the library works without a memory allocator, it is necessary
@@ -47,547 +132,454 @@ impl<'a> CayenneLPP<'a> {
     pub fn new(buffer: &'a mut [u8]) -> Self {
         CayenneLPP {
             buffer,
-            index: 0
+            index: 0,
+            registry: CayenneLPPRegistry::empty(),
+            rounding: Rounding::default(),
+            overflow: Overflow::default()
         }
     }
+}
 
-    /// Resets the index pointing into the buffer, so it is possible to reuse the buffer and add new payloads
-    /// to it.
-    /// Remark: the buffer is not cleared by this operation.
-    pub fn reset(&mut self) {
-        self.index = 0;
+impl<const N: usize> Default for CayenneLPP<'_, [u8; N]> {
+    fn default() -> Self {
+        CayenneLPP::from_storage([0; N])
     }
+}
 
-    /// Returns the slice of the buffer that contains the payload of the added data types.
-    pub fn payload_slice(&self) -> &[u8] {
-        &self.buffer[0..self.index]
-    }
-
-    /// Adds a scalar value to the data structure.
-    pub fn add_scalar(&mut self, scalar: &CayenneLPPScalar) -> Result<(), Error> {
-        let channel = scalar.channel;
-        match scalar.value {
-            CayenneLPPValue::DigitalInput(s) => self.add_digital_input(channel, s),
-            CayenneLPPValue::DigitalOutput(s) => self.add_digital_output(channel, s),
-            CayenneLPPValue::AnalogInput(s) => self.add_analog_input(channel, s),
-            CayenneLPPValue::AnalogOutput(s) => self.add_analog_output(channel, s),
-            CayenneLPPValue::GenericSensor(s) => self.add_generic_sensor(channel, s),
-            CayenneLPPValue::Luminosity(s) => self.add_luminosity(channel, s),
-            CayenneLPPValue::Presence(s) => self.add_presence(channel, s),
-            CayenneLPPValue::Temperature(s) => self.add_temperature(channel, s),
-            CayenneLPPValue::RelativeHumidity(s) => self.add_relative_humidity(channel, s),
-            CayenneLPPValue::Accelerometer(x, y, z) => self.add_accelerometer(channel, x, y, z),
-            CayenneLPPValue::BarometricPressure(s) => self.add_barometric_pressure(channel, s),
-            CayenneLPPValue::Voltage(s) => self.add_voltage(channel, s),
-            CayenneLPPValue::Current(s) => self.add_current(channel, s),
-            CayenneLPPValue::Frequency(s) => self.add_frequency(channel, s),
-            CayenneLPPValue::Percentage(s) => self.add_percentage(channel, s),
-            CayenneLPPValue::Altitude(s) => self.add_altitude(channel, s),
-            CayenneLPPValue::Concentration(s) => self.add_concentration(channel, s),
-            CayenneLPPValue::Power(s) => self.add_power(channel, s),
-            CayenneLPPValue::Distance(s) => self.add_distance(channel, s),
-            CayenneLPPValue::Energy(s) => self.add_energy(channel, s),
-            CayenneLPPValue::Direction(s) => self.add_direction(channel, s),
-            CayenneLPPValue::UnixTime(s) => self.add_unixtime(channel, s),
-            CayenneLPPValue::Gyrometer(x, y, z) => self.add_gyrometer(channel, x, y, z),
-            CayenneLPPValue::Color(r, g, b)        => self.add_color(channel, r, g, b),
-            CayenneLPPValue::GPS(lat, lon, alt) => self.add_gps(channel, lat, lon, alt),
-            CayenneLPPValue::Switch(s) => self.add_switch(channel, s),
-        }
+#[cfg(feature = "alloc")]
+impl Default for CayenneLPP<'_, Vec<u8>> {
+    fn default() -> Self {
+        CayenneLPP::from_storage(Vec::new())
     }
+}
 
-    /// Adds the payload for a digital input to the Cayenne LPP data structure.
-    pub fn add_digital_input(&mut self, channel: u8, value: u8) -> Result<(), Error> {
-        if self.index + LPP_DIGITAL_INPUT_SIZE > self.buffer.len() {
-            return Err(Error::InsufficientMemory);
-        }
-
-        self.buffer[self.index] = channel;
-        self.buffer[{ self.index += 1; self.index }] = LPP_DIGITAL_INPUT;
-        self.buffer[{ self.index += 1; self.index }] = value;
-        self.index += 1;
-
-        Ok(())
+#[cfg(feature = "heapless")]
+impl<const N: usize> Default for CayenneLPP<'_, heapless::Vec<u8, N>> {
+    fn default() -> Self {
+        CayenneLPP::from_storage(heapless::Vec::new())
     }
+}
 
-    /// Adds the payload for a digital output to the Cayenne LPP data structure.
-    pub fn add_digital_output(&mut self, channel: u8, value: u8) -> Result<(), Error> {
-        if self.index + LPP_DIGITAL_OUTPUT_SIZE > self.buffer.len() {
-            return Err(Error::InsufficientMemory);
-        }
+impl<'a, S: CayenneLPPStorage> CayenneLPP<'a, S> {
 
-        self.buffer[self.index] = channel;
-        self.buffer[{ self.index += 1; self.index }] = LPP_DIGITAL_OUTPUT;
-        self.buffer[{ self.index += 1; self.index }] = value;
-        self.index += 1;
+    /// Creates a new Cayenne LPP data structure that writes its payload into the given storage, e.g., an owned array.
+    pub fn from_storage(storage: S) -> Self {
+        CayenneLPP {
+            buffer: storage,
+            index: 0,
+            registry: CayenneLPPRegistry::empty(),
+            rounding: Rounding::default(),
+            overflow: Overflow::default()
+        }
+    }
 
-        Ok(())
+    /// Consumes the data structure and returns its storage. The payload is located at the beginning of the storage
+    /// and has the length of `payload_slice()`. Growing storages (`Vec<u8>` and `heapless::Vec<u8, N>`) only contain
+    /// the payload.
+    pub fn into_storage(self) -> S {
+        self.buffer
     }
 
-    /// Adds the payload for an analog input to the Cayenne LPP data structure.
-    pub fn add_analog_input(&mut self, channel: u8, value: f32) -> Result<(), Error> {
-        if self.index + LPP_ANALOG_INPUT_SIZE > self.buffer.len() {
-            return Err(Error::InsufficientMemory);
+    /// Sets the rounding policy that is used by all functions that convert a floating point value to the integer
+    /// units of the payload (e.g., `add_temperature()`). By default, values are rounded half away from zero.
+    pub fn with_rounding(self, rounding: Rounding) -> Self {
+        CayenneLPP {
+            rounding,
+            ..self
         }
+    }
 
-        let analog_input: i16 = (value * 100.0) as i16;
-        let analog_input_bytes = analog_input.to_be_bytes();
-
-        self.buffer[self.index] = channel;
-        self.buffer[{ self.index += 1; self.index }] = LPP_ANALOG_INPUT;
-        self.buffer[{ self.index += 1; self.index }] = analog_input_bytes[0];
-        self.buffer[{ self.index += 1; self.index }] = analog_input_bytes[1];
-        self.index += 1;
-
-        Ok(())
+    /// Returns the rounding policy of the data structure.
+    pub fn rounding(&self) -> Rounding {
+        self.rounding
     }
 
-    /// Adds the payload for an analog output to the Cayenne LPP data structure.
-    pub fn add_analog_output(&mut self, channel: u8, value: f32) -> Result<(), Error> {
-        if self.index + LPP_ANALOG_OUTPUT_SIZE > self.buffer.len() {
-            return Err(Error::InsufficientMemory);
+    /// Sets the policy for values that exceed the range of the payload. By default, these values are rejected with
+    /// `Error::OutOfRange`. The `add_*()` functions report whether a value has been clamped or wrapped.
+    pub fn with_overflow(self, overflow: Overflow) -> Self {
+        CayenneLPP {
+            overflow,
+            ..self
         }
+    }
 
-        let analog_output: i16 = (value * 100.0) as i16;
-        let analog_output_bytes = analog_output.to_be_bytes();
-
-        self.buffer[self.index] = channel;
-        self.buffer[{ self.index += 1; self.index }] = LPP_ANALOG_OUTPUT;
-        self.buffer[{ self.index += 1; self.index }] = analog_output_bytes[0];
-        self.buffer[{ self.index += 1; self.index }] = analog_output_bytes[1];
-        self.index += 1;
+    /// Returns the overflow policy of the data structure.
+    pub fn overflow(&self) -> Overflow {
+        self.overflow
+    }
 
-        Ok(())
+    /// Makes sure that the storage can hold a value with `size` bytes after the current payload.
+    fn reserve(&mut self, size: usize) -> Result<(), Error> {
+        self.buffer.reserve(self.index + size)
     }
 
-    /// Adds the payload for a generic sensor to the Cayenne LPP data structure. The units are not specified.
-    pub fn add_generic_sensor(&mut self, channel: u8, value: u32) -> Result<(), Error> {
-        if self.index + LPP_GENERIC_SENSOR_SIZE > self.buffer.len() {
-            return Err(Error::InsufficientMemory);
+    /// Sets the registry with the custom types that can be added with `add_custom()` and that are unpacked by the
+    /// iterators.
+    pub fn with_registry(self, registry: CayenneLPPRegistry<'a>) -> Self {
+        CayenneLPP {
+            registry,
+            ..self
         }
+    }
 
-        let value_bytes = value.to_be_bytes();
+    /// Resets the index pointing into the buffer, so it is possible to reuse the buffer and add new payloads
+    /// to it.
+    /// Remark: the buffer is not cleared by this operation, but growing storages are shrunk to the empty payload.
+    pub fn reset(&mut self) {
+        self.index = 0;
+        self.buffer.truncate(0);
+    }
 
-        self.buffer[self.index] = channel;
-        self.buffer[{ self.index += 1; self.index }] = LPP_GENERIC_SENSOR;
-        self.buffer[{ self.index += 1; self.index }] = value_bytes[0];
-        self.buffer[{ self.index += 1; self.index }] = value_bytes[1];
-        self.buffer[{ self.index += 1; self.index }] = value_bytes[2];
-        self.buffer[{ self.index += 1; self.index }] = value_bytes[3];
-        self.index += 1;
+    /// Returns the slice of the buffer that contains the payload of the added data types.
+    pub fn payload_slice(&self) -> &[u8] {
+        &self.buffer.as_bytes()[0..self.index]
+    }
 
-        Ok(())
+    /// Returns a checkpoint with the current end of the payload, so values that are added afterwards can be removed
+    /// again with `rollback()`.
+    pub fn checkpoint(&self) -> CayenneLPPCheckpoint {
+        CayenneLPPCheckpoint { index: self.index }
     }
 
-    /// Adds the payload for luminosity to the Cayenne LPP data structure. The value should be provided in lux.
-    pub fn add_luminosity(&mut self, channel: u8, lux: u16) -> Result<(), Error> {
-        if self.index + LPP_LUMINOSITY_SIZE > self.buffer.len() {
-            return Err(Error::InsufficientMemory);
-        }
+    /// Removes all values that were added after the checkpoint was taken. Like `reset()`, the buffer is not cleared.
+    /// A checkpoint that lies behind the current end of the payload (e.g., after a `reset()`) does not change
+    /// the payload.
+    pub fn rollback(&mut self, checkpoint: CayenneLPPCheckpoint) {
+        self.index = self.index.min(checkpoint.index);
+        self.buffer.truncate(self.index);
+    }
 
-        self.buffer[self.index] = channel;
-        self.buffer[{ self.index += 1; self.index }] = LPP_LUMINOSITY;
-        self.buffer[{ self.index += 1; self.index }] = (lux >> 8) as u8;
-        self.buffer[{ self.index += 1; self.index }] = lux as u8;
-        self.index += 1;
+    /// Creates a failable iterator over the scalars that were added to the data structure. In contrast to
+    /// `into_iter()`, the data structure is not consumed and only the payload slice is decoded, so bytes that are
+    /// left in the buffer from before a `reset()` are not returned.
+    pub fn iter(&self) -> CayenneLPPIntoFailableIterator<'_> {
+        CayenneLPPDecoder::new(self.payload_slice()).with_registry(self.registry).iter()
+    }
 
-        Ok(())
+    /// Creates a failable iterator over the raw values that were added to the data structure. Like `iter()`, only
+    /// the payload slice is decoded.
+    pub fn raw_iter(&self) -> CayenneLPPRawIterator<'_> {
+        CayenneLPPDecoder::new(self.payload_slice()).with_registry(self.registry).raw_iter()
     }
 
-    /// Adds the payload for a presence sensor to the Cayenne LPP data structure.
-    pub fn add_presence(&mut self, channel: u8, value: u8) -> Result<(), Error> {
-        if self.index + LPP_PRESENCE_SIZE > self.buffer.len() {
-            return Err(Error::InsufficientMemory);
-        }
+    /// Creates an infallable iterator over the scalars that were added to the data structure. Like `iter()`, only the
+    /// payload slice is decoded, and the iteration stops at the first scalar that cannot be unpacked.
+    pub fn infailable_iter(&self) -> CayenneLPPIterator<'_> {
+        CayenneLPPIterator { failable_iterator: self.iter() }
+    }
 
-        self.buffer[self.index] = channel;
-        self.buffer[{ self.index += 1; self.index }] = LPP_PRESENCE;
-        self.buffer[{ self.index += 1; self.index }] = value;
-        self.index += 1;
+    /// Adds a scalar value to the data structure. The value is scaled to the raw units of its data type, using the
+    /// rounding and the overflow policy of the data structure, and written like a raw scalar value.
+    pub fn add_scalar(&mut self, scalar: &CayenneLPPScalar) -> Result<Encoded, Error> {
+        let (value, encoded) = scalar.value.to_raw(&self.registry, self.rounding, self.overflow)?;
+        let written = self.add_raw_scalar(&CayenneLPPRawScalar { channel: scalar.channel, value })?;
 
-        Ok(())
+        Ok(encoded.and(written))
     }
 
-    /// Adds the payload for temperature to the Cayenne LPP data structure.
-    pub fn add_temperature(&mut self, channel: u8, celsius: f32) -> Result<(), Error> {
-        if self.index + LPP_TEMPERATURE_SIZE > self.buffer.len() {
-            return Err(Error::InsufficientMemory);
+    /// Adds all scalar values to the data structure in the given order, using the same logic as `add_scalar()`.
+    /// If one of the values cannot be added (e.g., because the buffer is too small), the error is returned and the
+    /// values that were already added by this call are removed, so the payload stays unchanged.
+    pub fn add_batch(&mut self, scalars: &[CayenneLPPScalar]) -> Result<Encoded, Error> {
+        let checkpoint = self.checkpoint();
+        let mut encoded = Encoded::Exact;
+
+        for scalar in scalars {
+            match self.add_scalar(scalar) {
+                Ok(result) => encoded = encoded.and(result),
+                Err(error) => {
+                    self.rollback(checkpoint);
+                    return Err(error);
+                }
+            }
         }
 
-        let temperature: i16 = (celsius * 10.0) as i16;
-        let temperature_bytes = temperature.to_be_bytes();
+        Ok(encoded)
+    }
 
-        self.buffer[self.index] = channel;
-        self.buffer[{ self.index += 1; self.index }] = LPP_TEMPERATURE;
-        self.buffer[{ self.index += 1; self.index }] = temperature_bytes[0];
-        self.buffer[{ self.index += 1; self.index }] = temperature_bytes[1];
-        self.index += 1;
+    /// Adds a raw scalar value to the data structure. All data types are written from their metadata in
+    /// [`LPP_TYPES`].
+    pub fn add_raw_scalar(&mut self, scalar: &CayenneLPPRawScalar) -> Result<Encoded, Error> {
+        let raw = scalar.value.to_fields();
+        let Some(lpp_type) = scalar.value.kind() else {
+            // custom types are encoded by their registry and only have a single value
+            return self.add_custom_raw(scalar.channel, scalar.value.type_code(), raw[0]);
+        };
 
-        Ok(())
+        self.add_fields(scalar.channel, lpp_type, raw)
     }
 
-    /// Adds the payload for relative humidity to the Cayenne LPP data structure.
-    pub fn add_relative_humidity(&mut self, channel: u8, relative_humidity: f32) -> Result<(), Error> {
-        if self.index + LPP_RELATIVE_HUMIDITY_SIZE > self.buffer.len() {
-            return Err(Error::InsufficientMemory);
-        }
+    /// Scales the values of a data type to the raw units of its fields, using the rounding and the overflow policy of
+    /// the data structure, and adds them to the payload.
+    #[inline(always)]
+    fn add_values<T: FieldValue, const N: usize>(&mut self, channel: u8, lpp_type: LppType, values: [T; N])
+        -> Result<Encoded, Error> {
+        let (raw, encoded) = to_fields(values, lpp_type, self.rounding, self.overflow)?;
+        self.write_fields(channel, lpp_type, raw)?;
 
-        let scaled_value = relative_humidity * 2.0;
-        let fraction = scaled_value % 1.0;
-        let whole_number = scaled_value - fraction;
-        let rounded_value = match (whole_number, fraction) {
-            // Greater than zero, Round toward zero
-            (s, f) if s > 0.0 && f < 0.5 => {
-                s
-            },
-            // Greater than zero, round away from zero
-            (s, f) if s > 0.0 && f >= 0.5 => {
-                s + 1.0
-            }
-            // Less than zero, round toward zero
-            (s, f) if s < 0.0 && f > -0.5 => {
-                s
-            },
-            // Less than zero, round away from zero
-            (s, f) if s < 0.0 && f <= -0.5 => {
-                s - 1.0
-            },
-            // Should never happen, just clamp if it does...
-            (s, _) => {
-                s
-            }
-        };
+        Ok(encoded)
+    }
 
-        self.buffer[self.index] = channel;
-        self.buffer[{ self.index += 1; self.index }] = LPP_RELATIVE_HUMIDITY;
-        self.buffer[{ self.index += 1; self.index }] = rounded_value as u8;
-        self.index += 1;
+    /// Adds the raw values of the fields of a data type to the payload. Every raw value is checked against the range
+    /// of its field in [`LPP_TYPES`] before the payload is modified.
+    #[inline(always)]
+    fn add_fields<const N: usize>(&mut self, channel: u8, lpp_type: LppType, raw: [i64; N]) -> Result<Encoded, Error> {
+        let mut values = [0; N];
+        let mut encoded = Encoded::Exact;
+        for ((value, raw), field) in values.iter_mut().zip(raw).zip(lpp_type.info().fields) {
+            let (limited, field_encoded) = self.overflow.limit(raw, field.raw_min, field.raw_max)?;
+            *value = limited;
+            encoded = encoded.and(field_encoded);
+        }
 
-        Ok(())
+        self.write_fields(channel, lpp_type, values)?;
+        Ok(encoded)
     }
 
-    /// Adds the payload of an accelerometer to the Cayenne LPP data structure.
-    pub fn add_accelerometer(&mut self, channel: u8, x: f32, y: f32, z: f32) -> Result<(), Error> {
-        if self.index + LPP_ACCELEROMETER_SIZE > self.buffer.len() {
-            return Err(Error::InsufficientMemory);
+    /// Writes an entry of a data type with the raw values of its fields, which are already limited to their ranges.
+    /// The values are written in big-endian order with the number of bytes of their field.
+    #[inline(always)]
+    fn write_fields<const N: usize>(&mut self, channel: u8, lpp_type: LppType, values: [i64; N]) -> Result<(), Error> {
+        let size = lpp_type.size();
+        self.reserve(size)?;
+
+        // the size of the entry is constant, so the writes into it do not need any bounds checks
+        let entry = &mut self.buffer.as_bytes_mut()[self.index..self.index + size];
+        entry[0] = channel;
+        entry[1] = lpp_type.code();
+        let mut offset = 2;
+        for (value, field) in values.iter().zip(lpp_type.info().fields) {
+            // only the lower bytes are written, so wrapped values keep the bits that fit into the payload
+            for byte in (0..field.bytes).rev() {
+                entry[offset] = (value >> (8 * byte)) as u8;
+                offset += 1;
+            }
         }
-
-        // prepare axis values
-        let vx: i16 = (x * 1000.0) as i16;
-        let vy: i16 = (y * 1000.0) as i16;
-        let vz: i16 = (z * 1000.0) as i16;
-
-        self.buffer[self.index] = channel;
-        self.buffer[{ self.index += 1; self.index }] = LPP_ACCELEROMETER;
-        self.buffer[{ self.index += 1; self.index }] = (vx >> 8) as u8;
-        self.buffer[{ self.index += 1; self.index }] = vx as u8;
-        self.buffer[{ self.index += 1; self.index }] = (vy >> 8) as u8;
-        self.buffer[{ self.index += 1; self.index }] = vy as u8;
-        self.buffer[{ self.index += 1; self.index }] = (vz >> 8) as u8;
-        self.buffer[{ self.index += 1; self.index }] = vz as u8;
-        self.index += 1;
+        self.index += size;
 
         Ok(())
     }
 
-    /// Adds the payload for barometric pressure to the Cayenne LPP data structure.
-    pub fn add_barometric_pressure(&mut self, channel: u8, hpa: f32) -> Result<(), Error> {
-        if self.index + LPP_BAROMETRIC_PRESSURE_SIZE > self.buffer.len() {
-            return Err(Error::InsufficientMemory);
-        }
+    /// Adds the payload for a digital input to the Cayenne LPP data structure.
+    pub fn add_digital_input(&mut self, channel: u8, value: u8) -> Result<Encoded, Error> {
+        self.add_fields(channel, LppType::DigitalInput, [value.into()])
+    }
 
-        let pressure = (hpa * 10.0) as u16;
+    /// Adds the payload for a digital output to the Cayenne LPP data structure.
+    pub fn add_digital_output(&mut self, channel: u8, value: u8) -> Result<Encoded, Error> {
+        self.add_fields(channel, LppType::DigitalOutput, [value.into()])
+    }
 
-        self.buffer[self.index] = channel;
-        self.buffer[{ self.index += 1; self.index }] = LPP_BAROMETRIC_PRESSURE;
-        self.buffer[{ self.index += 1; self.index }] = (pressure >> 8) as u8;
-        self.buffer[{ self.index += 1; self.index }] = pressure as u8;
-        self.index += 1;
+    /// Adds the payload for an analog input to the Cayenne LPP data structure.
+    pub fn add_analog_input(&mut self, channel: u8, value: f32) -> Result<Encoded, Error> {
+        self.add_values(channel, LppType::AnalogInput, [value])
+    }
 
-        Ok(())
+    /// Adds the payload for an analog input to the Cayenne LPP data structure. The value is given in the raw
+    /// units of the payload (0.01).
+    pub fn add_analog_input_raw(&mut self, channel: u8, value: i16) -> Result<Encoded, Error> {
+        self.add_fields(channel, LppType::AnalogInput, [value.into()])
     }
 
-    /// Adds the payload for a voltage to the Cayenne LPP data structure (in volts)
-    pub fn add_voltage(&mut self, channel: u8, voltage: f32) -> Result<(), Error> {
-        if self.index + LPP_VOLTAGE_SIZE > self.buffer.len() {
-            return Err(Error::InsufficientMemory);
-        }
+    /// Adds the payload for an analog output to the Cayenne LPP data structure.
+    pub fn add_analog_output(&mut self, channel: u8, value: f32) -> Result<Encoded, Error> {
+        self.add_values(channel, LppType::AnalogOutput, [value])
+    }
 
-        if voltage * 100.0 > u16::MAX as f32 {
-            return Err(Error::OutOfRange);
-        }
-    
-        let voltage: u16 = (voltage * 100.0) as u16;
-
-        self.buffer[self.index] = channel;
-        self.buffer[{ self.index += 1; self.index }] = LPP_VOLTAGE;
-        self.buffer[{ self.index += 1; self.index }] = (voltage >> 8) as u8;
-        self.buffer[{ self.index += 1; self.index }] = voltage as u8;
-        self.index += 1;
-        
-        Ok(())
+    /// Adds the payload for an analog output to the Cayenne LPP data structure. The value is given in the raw
+    /// units of the payload (0.01).
+    pub fn add_analog_output_raw(&mut self, channel: u8, value: i16) -> Result<Encoded, Error> {
+        self.add_fields(channel, LppType::AnalogOutput, [value.into()])
     }
 
-    /// Adds the payload for a current to the Cayenne LPP data structure (in amps)
-    pub fn add_current(&mut self, channel: u8, amperage: f32) -> Result<(), Error> {
-        if self.index + LPP_CURRENT_SIZE > self.buffer.len() {
-            return Err(Error::InsufficientMemory);
-        }
+    /// Adds the payload for a generic sensor to the Cayenne LPP data structure. The units are not specified.
+    pub fn add_generic_sensor(&mut self, channel: u8, value: u32) -> Result<Encoded, Error> {
+        self.add_fields(channel, LppType::GenericSensor, [value.into()])
+    }
 
-        if amperage * 1000.0 > u16::MAX as f32 {
-            return Err(Error::OutOfRange);
-        }
-    
-        let amperage: u16 = (amperage * 1000.0) as u16;
-
-        self.buffer[self.index] = channel;
-        self.buffer[{ self.index += 1; self.index }] = LPP_CURRENT;
-        self.buffer[{ self.index += 1; self.index }] = (amperage >> 8) as u8;
-        self.buffer[{ self.index += 1; self.index }] = amperage as u8;
-        self.index += 1;
-        
-        Ok(())
+    /// Adds the payload for luminosity to the Cayenne LPP data structure. The value should be provided in lux.
+    pub fn add_luminosity(&mut self, channel: u8, lux: u16) -> Result<Encoded, Error> {
+        self.add_fields(channel, LppType::Luminosity, [lux.into()])
     }
 
-    /// Adds the payload for a frequency to the Cayenne LPP data structure. The units are in hertz
-    pub fn add_frequency(&mut self, channel: u8, frequency: u32) -> Result<(), Error> {
-        if self.index + LPP_FREQUENCY_SIZE > self.buffer.len() {
-            return Err(Error::InsufficientMemory);
-        }
+    /// Adds the payload for a presence sensor to the Cayenne LPP data structure.
+    pub fn add_presence(&mut self, channel: u8, value: u8) -> Result<Encoded, Error> {
+        self.add_fields(channel, LppType::Presence, [value.into()])
+    }
 
-        let frequency_bytes = frequency.to_be_bytes();
+    /// Adds the payload for temperature to the Cayenne LPP data structure.
+    pub fn add_temperature(&mut self, channel: u8, celsius: f32) -> Result<Encoded, Error> {
+        self.add_values(channel, LppType::Temperature, [celsius])
+    }
 
-        self.buffer[self.index] = channel;
-        self.buffer[{ self.index += 1; self.index }] = LPP_FREQUENCY;
-        self.buffer[{ self.index += 1; self.index }] = frequency_bytes[0];
-        self.buffer[{ self.index += 1; self.index }] = frequency_bytes[1];
-        self.buffer[{ self.index += 1; self.index }] = frequency_bytes[2];
-        self.buffer[{ self.index += 1; self.index }] = frequency_bytes[3];
-        self.index += 1;
+    /// Adds the payload for temperature to the Cayenne LPP data structure. The value is given in the raw units of
+    /// the payload (0.1 °C).
+    pub fn add_temperature_raw(&mut self, channel: u8, decicelsius: i16) -> Result<Encoded, Error> {
+        self.add_fields(channel, LppType::Temperature, [decicelsius.into()])
+    }
 
-        Ok(())
+    /// Adds the payload for relative humidity to the Cayenne LPP data structure.
+    pub fn add_relative_humidity(&mut self, channel: u8, relative_humidity: f32) -> Result<Encoded, Error> {
+        self.add_values(channel, LppType::RelativeHumidity, [relative_humidity])
     }
 
-    /// Adds the payload for a percentage to the CayenneLPP data structure.  The units are single percent (0-100)%
-    pub fn add_percentage(&mut self, channel: u8, percentage: u8) -> Result<(), Error> {
-        if self.index + LPP_PERCENTAGE_SIZE > self.buffer.len() {
-            return Err(Error::InsufficientMemory);
-        }
+    /// Adds the payload for relative humidity to the Cayenne LPP data structure. The value is given in the raw
+    /// units of the payload (0.5 %).
+    pub fn add_relative_humidity_raw(&mut self, channel: u8, half_percent: u8) -> Result<Encoded, Error> {
+        self.add_fields(channel, LppType::RelativeHumidity, [half_percent.into()])
+    }
 
-        self.buffer[self.index] = channel;
-        self.buffer[{ self.index += 1; self.index }] = LPP_PERCENTAGE;
-        self.buffer[{ self.index += 1; self.index }] = percentage;
-        self.index += 1;
+    /// Adds the payload of an accelerometer to the Cayenne LPP data structure.
+    pub fn add_accelerometer(&mut self, channel: u8, x: f32, y: f32, z: f32) -> Result<Encoded, Error> {
+        self.add_values(channel, LppType::Accelerometer, [x, y, z])
+    }
 
-        Ok(())
+    /// Adds the payload of an accelerometer to the Cayenne LPP data structure. The values are given in the raw
+    /// units of the payload (0.001 G).
+    pub fn add_accelerometer_raw(&mut self, channel: u8, x: i16, y: i16, z: i16) -> Result<Encoded, Error> {
+        self.add_fields(channel, LppType::Accelerometer, [x.into(), y.into(), z.into()])
     }
 
-    /// Adds the payload for an altitude to the Cayenne LPP data structure (in meters)
-    pub fn add_altitude(&mut self, channel: u8, altitude: i16) -> Result<(), Error> {
-        if self.index + LPP_ALTITUDE_SIZE > self.buffer.len() {
-            return Err(Error::InsufficientMemory);
-        }
+    /// Adds the payload for barometric pressure to the Cayenne LPP data structure.
+    pub fn add_barometric_pressure(&mut self, channel: u8, hpa: f32) -> Result<Encoded, Error> {
+        self.add_values(channel, LppType::BarometricPressure, [hpa])
+    }
 
-        self.buffer[self.index] = channel;
-        self.buffer[{ self.index += 1; self.index }] = LPP_ALTITUDE;
-        self.buffer[{ self.index += 1; self.index }] = (altitude >> 8) as u8;
-        self.buffer[{ self.index += 1; self.index }] = altitude as u8;
-        self.index += 1;
-        
-        Ok(())
+    /// Adds the payload for barometric pressure to the Cayenne LPP data structure. The value is given in the raw
+    /// units of the payload (0.1 hPa).
+    pub fn add_barometric_pressure_raw(&mut self, channel: u8, decihpa: u16) -> Result<Encoded, Error> {
+        self.add_fields(channel, LppType::BarometricPressure, [decihpa.into()])
     }
 
-    /// Adds the payload for a power to the Cayenne LPP data structure (in watts)
-    pub fn add_power(&mut self, channel: u8, power: u16) -> Result<(), Error> {
-        if self.index + LPP_POWER_SIZE > self.buffer.len() {
-            return Err(Error::InsufficientMemory);
-        }
+    /// Adds the payload for a voltage to the Cayenne LPP data structure (in volts)
+    pub fn add_voltage(&mut self, channel: u8, voltage: f32) -> Result<Encoded, Error> {
+        self.add_values(channel, LppType::Voltage, [voltage])
+    }
 
-        self.buffer[self.index] = channel;
-        self.buffer[{ self.index += 1; self.index }] = LPP_POWER;
-        self.buffer[{ self.index += 1; self.index }] = (power >> 8) as u8;
-        self.buffer[{ self.index += 1; self.index }] = power as u8;
-        self.index += 1;
-        
-        Ok(())
+    /// Adds the payload for a voltage to the Cayenne LPP data structure. The value is given in the raw units of
+    /// the payload (0.01 V).
+    pub fn add_voltage_raw(&mut self, channel: u8, centivolts: u16) -> Result<Encoded, Error> {
+        self.add_fields(channel, LppType::Voltage, [centivolts.into()])
     }
 
-    /// Adds the payload for a frequency to the Cayenne LPP data structure. The units are in millimeters
-    pub fn add_distance(&mut self, channel: u8, distance: u32) -> Result<(), Error> {
-        if self.index + LPP_DISTANCE_SIZE > self.buffer.len() {
-            return Err(Error::InsufficientMemory);
-        }
+    /// Adds the payload for a current to the Cayenne LPP data structure (in amps)
+    pub fn add_current(&mut self, channel: u8, amperage: f32) -> Result<Encoded, Error> {
+        self.add_values(channel, LppType::Current, [amperage])
+    }
 
-        let distance_bytes = distance.to_be_bytes();
+    /// Adds the payload for a current to the Cayenne LPP data structure. The value is given in the raw units of
+    /// the payload (1 mA).
+    pub fn add_current_raw(&mut self, channel: u8, milliamperes: u16) -> Result<Encoded, Error> {
+        self.add_fields(channel, LppType::Current, [milliamperes.into()])
+    }
 
-        self.buffer[self.index] = channel;
-        self.buffer[{ self.index += 1; self.index }] = LPP_DISTANCE;
-        self.buffer[{ self.index += 1; self.index }] = distance_bytes[0];
-        self.buffer[{ self.index += 1; self.index }] = distance_bytes[1];
-        self.buffer[{ self.index += 1; self.index }] = distance_bytes[2];
-        self.buffer[{ self.index += 1; self.index }] = distance_bytes[3];
-        self.index += 1;
+    /// Adds the payload for a frequency to the Cayenne LPP data structure. The units are in hertz
+    pub fn add_frequency(&mut self, channel: u8, frequency: u32) -> Result<Encoded, Error> {
+        self.add_fields(channel, LppType::Frequency, [frequency.into()])
+    }
 
-        Ok(())
+    /// Adds the payload for a percentage to the CayenneLPP data structure.  The units are single percent (0-100)%
+    pub fn add_percentage(&mut self, channel: u8, percentage: u8) -> Result<Encoded, Error> {
+        self.add_fields(channel, LppType::Percentage, [percentage.into()])
     }
 
-    /// Adds the payload for energy to the Cayenne LPP data structure. The units are in single Wh
-    pub fn add_energy(&mut self, channel: u8, energy: u32) -> Result<(), Error> {
-        if self.index + LPP_ENERGY_SIZE > self.buffer.len() {
-            return Err(Error::InsufficientMemory);
-        }
+    /// Adds the payload for an altitude to the Cayenne LPP data structure (in meters)
+    pub fn add_altitude(&mut self, channel: u8, altitude: i16) -> Result<Encoded, Error> {
+        self.add_fields(channel, LppType::Altitude, [altitude.into()])
+    }
 
-        let energy_bytes = energy.to_be_bytes();
+    /// Adds the payload for a power to the Cayenne LPP data structure (in watts)
+    pub fn add_power(&mut self, channel: u8, power: u16) -> Result<Encoded, Error> {
+        self.add_fields(channel, LppType::Power, [power.into()])
+    }
 
-        self.buffer[self.index] = channel;
-        self.buffer[{ self.index += 1; self.index }] = LPP_ENERGY;
-        self.buffer[{ self.index += 1; self.index }] = energy_bytes[0];
-        self.buffer[{ self.index += 1; self.index }] = energy_bytes[1];
-        self.buffer[{ self.index += 1; self.index }] = energy_bytes[2];
-        self.buffer[{ self.index += 1; self.index }] = energy_bytes[3];
-        self.index += 1;
+    /// Adds the payload for a frequency to the Cayenne LPP data structure. The units are in millimeters
+    pub fn add_distance(&mut self, channel: u8, distance: u32) -> Result<Encoded, Error> {
+        self.add_fields(channel, LppType::Distance, [distance.into()])
+    }
 
-        Ok(())
+    /// Adds the payload for energy to the Cayenne LPP data structure. The units are in single Wh
+    pub fn add_energy(&mut self, channel: u8, energy: u32) -> Result<Encoded, Error> {
+        self.add_fields(channel, LppType::Energy, [energy.into()])
     }
 
     /// Adds the payload for a direction to the Cayenne LPP data structure (in degrees)
-    pub fn add_direction(&mut self, channel: u8, direction: u16) -> Result<(), Error> {
-        if self.index + LPP_DIRECTION_SIZE > self.buffer.len() {
-            return Err(Error::InsufficientMemory);
-        }
-
-        self.buffer[self.index] = channel;
-        self.buffer[{ self.index += 1; self.index }] = LPP_DIRECTION;
-        self.buffer[{ self.index += 1; self.index }] = (direction >> 8) as u8;
-        self.buffer[{ self.index += 1; self.index }] = direction as u8;
-        self.index += 1;
-        
-        Ok(())
+    pub fn add_direction(&mut self, channel: u8, direction: u16) -> Result<Encoded, Error> {
+        self.add_fields(channel, LppType::Direction, [direction.into()])
     }
 
     /// Adds the payload for a unixtime to the Cayenne LPP data structure.
     /// The units are in seconds, and it's relative to unix epoch
-    pub fn add_unixtime(&mut self, channel: u8, unixtime: u32) -> Result<(), Error> {
-        if self.index + LPP_UNIXTIME_SIZE > self.buffer.len() {
-            return Err(Error::InsufficientMemory);
-        }
-
-        let unixtime_bytes = unixtime.to_be_bytes();
-
-        self.buffer[self.index] = channel;
-        self.buffer[{ self.index += 1; self.index }] = LPP_UNIXTIME;
-        self.buffer[{ self.index += 1; self.index }] = unixtime_bytes[0];
-        self.buffer[{ self.index += 1; self.index }] = unixtime_bytes[1];
-        self.buffer[{ self.index += 1; self.index }] = unixtime_bytes[2];
-        self.buffer[{ self.index += 1; self.index }] = unixtime_bytes[3];
-        self.index += 1;
-
-        Ok(())
+    pub fn add_unixtime(&mut self, channel: u8, unixtime: u32) -> Result<Encoded, Error> {
+        self.add_fields(channel, LppType::UnixTime, [unixtime.into()])
     }
 
     /// Adds the payload for a gyrometer to the Cayenne LPP data structure.
-    pub fn add_gyrometer(&mut self, channel: u8, x: f32, y: f32, z: f32) -> Result<(), Error> {
-        if self.index + LPP_GYROMETER_SIZE > self.buffer.len() {
-            return Err(Error::InsufficientMemory);
-        }
-
-        // prepare axis values
-        let vx: u16 = (x * 100.0) as u16;
-        let vy: u16 = (y * 100.0) as u16;
-        let vz: u16 = (z * 100.0) as u16;
-
-        self.buffer[self.index] = channel;
-        self.buffer[{ self.index += 1; self.index }] = LPP_GYROMETER;
-        self.buffer[{ self.index += 1; self.index }] = (vx >> 8) as u8;
-        self.buffer[{ self.index += 1; self.index }] = vx as u8;
-        self.buffer[{ self.index += 1; self.index }] = (vy >> 8) as u8;
-        self.buffer[{ self.index += 1; self.index }] = vy as u8;
-        self.buffer[{ self.index += 1; self.index }] = (vz >> 8) as u8;
-        self.buffer[{ self.index += 1; self.index }] = vz as u8;
-        self.index += 1;
+    pub fn add_gyrometer(&mut self, channel: u8, x: f32, y: f32, z: f32) -> Result<Encoded, Error> {
+        self.add_values(channel, LppType::Gyrometer, [x, y, z])
+    }
 
-        Ok(())
+    /// Adds the payload for a gyrometer to the Cayenne LPP data structure. The values are given in the raw units
+    /// of the payload (0.01 °/s).
+    pub fn add_gyrometer_raw(&mut self, channel: u8, x: u16, y: u16, z: u16) -> Result<Encoded, Error> {
+        self.add_fields(channel, LppType::Gyrometer, [x.into(), y.into(), z.into()])
     }
 
     /// Adds the payload for GPS to the Cayenne LPP data structure.
-    pub fn add_gps(&mut self, channel: u8, latitude: f32, longitude: f32, meters: f32) -> Result<(), Error> {
-        if self.index + LPP_GPS_SIZE > self.buffer.len() {
-            return Err(Error::InsufficientMemory);
-        }
-
-        // Do bounds-checking on the GPS values
-        if !(-90.0..=90.0).contains(&latitude) {
-            return Err(Error::OutOfRange);
-        }
-
-        if !(-180.0..= 180.0).contains(&longitude) {
-            return Err(Error::OutOfRange);
-        }
-
-        // prepare GPS values (3 bytes each)
-        let vx: i32 = (latitude * 10000.0) as i32;
-        let vy: i32 = (longitude * 10000.0) as i32;
-        let vz: i32 = (meters * 100.0) as i32;
-
-        self.buffer[self.index] = channel;
-        self.buffer[{ self.index += 1; self.index }] = LPP_GPS;
-        self.buffer[{ self.index += 1; self.index }] = (vx >> 16) as u8;
-        self.buffer[{ self.index += 1; self.index }] = (vx >> 8) as u8;
-        self.buffer[{ self.index += 1; self.index }] = vx as u8;
-        self.buffer[{ self.index += 1; self.index }] = (vy >> 16) as u8;
-        self.buffer[{ self.index += 1; self.index }] = (vy >> 8) as u8;
-        self.buffer[{ self.index += 1; self.index }] = vy as u8;
-        self.buffer[{ self.index += 1; self.index }] = (vz >> 16) as u8;
-        self.buffer[{ self.index += 1; self.index }] = (vz >> 8) as u8;
-        self.buffer[{ self.index += 1; self.index }] = vz as u8;
-        self.index += 1;
+    pub fn add_gps(&mut self, channel: u8, latitude: f32, longitude: f32, meters: f32) -> Result<Encoded, Error> {
+        self.add_values(channel, LppType::GPS, [latitude, longitude, meters])
+    }
 
-        Ok(())
+    /// Adds the payload for GPS to the Cayenne LPP data structure. The values are given in the raw units of the
+    /// payload (0.0001 ° for latitude and longitude, 0.01 m for the altitude).
+    pub fn add_gps_raw(&mut self, channel: u8, latitude: i32, longitude: i32, centimeters: i32) -> Result<Encoded, Error> {
+        self.add_fields(channel, LppType::GPS, [latitude.into(), longitude.into(), centimeters.into()])
     }
 
     /// Adds the payload for switch to the Cayenne LPP data structure. It's a byte that's just 0/1
-    pub fn add_switch(&mut self, channel: u8, value: bool) -> Result<(), Error> {
-        if self.index + LPP_SWITCH_SIZE > self.buffer.len() {
-            return Err(Error::InsufficientMemory);
-        }
-
-        self.buffer[self.index] = channel;
-        self.buffer[{ self.index += 1; self.index }] = LPP_SWITCH;
-        self.buffer[{ self.index += 1; self.index }] = if value { 1 } else { 0 };
-        self.index += 1;
-
-        Ok(())
+    pub fn add_switch(&mut self, channel: u8, value: bool) -> Result<Encoded, Error> {
+        self.add_fields(channel, LppType::Switch, [value.into()])
     }
 
     /// Adds the payload for a concentration to the Cayenne LPP data structure (in ppm)
-    pub fn add_concentration(&mut self, channel: u8, concentration: u16) -> Result<(), Error> {
-        if self.index + LPP_CONCENTRATION_SIZE > self.buffer.len() {
-            return Err(Error::InsufficientMemory);
-        }
-
-        self.buffer[self.index] = channel;
-        self.buffer[{ self.index += 1; self.index }] = LPP_CONCENTRATION;
-        self.buffer[{ self.index += 1; self.index }] = (concentration >> 8) as u8;
-        self.buffer[{ self.index += 1; self.index }] = concentration as u8;
-        self.index += 1;
-        
-        Ok(())
+    pub fn add_concentration(&mut self, channel: u8, concentration: u16) -> Result<Encoded, Error> {
+        self.add_fields(channel, LppType::Concentration, [concentration.into()])
     }
 
     /// Adds the payload for color to the Cayenne LPP data structure. It's a byte per-color channel
-    pub fn add_color(&mut self, channel: u8, red: u8, green: u8, blue: u8) -> Result<(), Error> {
-        if self.index + LPP_COLOR_SIZE > self.buffer.len() {
-            return Err(Error::InsufficientMemory);
+    pub fn add_color(&mut self, channel: u8, red: u8, green: u8, blue: u8) -> Result<Encoded, Error> {
+        self.add_fields(channel, LppType::Color, [red.into(), green.into(), blue.into()])
+    }
+
+    /// Adds the payload for a custom type to the Cayenne LPP data structure. The custom type has to be contained in
+    /// the registry of the data structure, otherwise `Error::UnhandledType` is returned.
+    pub fn add_custom(&mut self, channel: u8, type_code: u8, value: f32) -> Result<Encoded, Error> {
+        let value = CayenneLPPValue::Custom(type_code, value);
+        self.add_scalar(&CayenneLPPScalar { channel, value })
+    }
+
+    /// Adds the payload for a custom type to the Cayenne LPP data structure. The value is given in the raw units of
+    /// the custom type (i.e., in steps of its resolution). Values outside of the raw range of the custom type are
+    /// handled by the overflow policy of the data structure.
+    pub fn add_custom_raw(&mut self, channel: u8, type_code: u8, raw: i64) -> Result<Encoded, Error> {
+        let custom_type = self.registry.get(type_code).ok_or(Error::UnhandledType(type_code))?;
+        let (min, max) = custom_type.raw_range();
+        let (raw, encoded) = self.overflow.limit_custom(raw, min, max)?;
+
+        let size = custom_type.size();
+        self.reserve(size)?;
+
+        let buffer = self.buffer.as_bytes_mut();
+        if let Err(error) = custom_type.encode(raw, &mut buffer[self.index + 2..self.index + size]) {
+            self.buffer.truncate(self.index);
+            return Err(error);
         }
 
-        self.buffer[self.index] = channel;
-        self.buffer[{ self.index += 1; self.index }] = LPP_COLOR;
-        self.buffer[{ self.index += 1; self.index }] = red;
-        self.buffer[{ self.index += 1; self.index }] = green;
-        self.buffer[{ self.index += 1; self.index }] = blue;
-        self.index += 1;
+        buffer[self.index] = channel;
+        buffer[self.index + 1] = type_code;
+        self.index += size;
 
-        Ok(())
+        Ok(encoded)
     }
 }
 
@@ -596,10 +588,16 @@ impl<'a> IntoIterator for CayenneLPP<'a> {
     type IntoIter = CayenneLPPIntoFailableIterator<'a>;
 
     fn into_iter(self) -> Self::IntoIter {
-        CayenneLPPIntoFailableIterator {
-            lpp: self,
-            index: 0
-        }
+        CayenneLPPDecoder::new(self.buffer).with_registry(self.registry).iter()
+    }
+}
+
+impl<'b, S: CayenneLPPStorage> IntoIterator for &'b CayenneLPP<'_, S> {
+    type Item = Result<CayenneLPPScalar, Error>;
+    type IntoIter = CayenneLPPIntoFailableIterator<'b>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
 }
 
@@ -0,0 +1,503 @@
+use crate::error::Error;
+
+/// Data types of the Cayenne LPP specification that are handled by this library. The discriminant of every variant is
+/// its type code in the payload.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum LppType {
+    /// Data type of a digital input
+    DigitalInput = 0,
+
+    /// Data type of a digital output
+    DigitalOutput = 1,
+
+    /// Data type of an analog input
+    AnalogInput = 2,
+
+    /// Data type of an analog output
+    AnalogOutput = 3,
+
+    /// Data type of a generic sensor
+    GenericSensor = 100,
+
+    /// Data type of a luminosity value
+    Luminosity = 101,
+
+    /// Data type of a presence sensor
+    Presence = 102,
+
+    /// Data type of a temperature value
+    Temperature = 103,
+
+    /// Data type of a relative humidity value
+    RelativeHumidity = 104,
+
+    /// Data type of accelerometer values
+    Accelerometer = 113,
+
+    /// Data type of a barometric pressure value
+    BarometricPressure = 115,
+
+    /// Data type of a voltage value
+    Voltage = 116,
+
+    /// Data type of a current value
+    Current = 117,
+
+    /// Data type of a frequency value
+    Frequency = 118,
+
+    /// Data type of a percentage
+    Percentage = 120,
+
+    /// Data type of an altitude
+    Altitude = 121,
+
+    /// Data type of a concentration
+    Concentration = 125,
+
+    /// Data type of a power value
+    Power = 128,
+
+    /// Data type of a distance value
+    Distance = 130,
+
+    /// Data type of an energy value
+    Energy = 131,
+
+    /// Data type of a direction value
+    Direction = 132,
+
+    /// Data type of a time (unix timestamp)
+    UnixTime = 133,
+
+    /// Data type of gyrometer values
+    Gyrometer = 134,
+
+    /// Data type of a color value
+    Color = 135,
+
+    /// Data type of GPS value
+    GPS = 136,
+
+    /// Data type of switch value
+    Switch = 142,
+}
+
+impl LppType {
+    /// All data types that are handled by this library, ordered by their type code
+    pub const ALL: [LppType; 26] = [
+        LppType::DigitalInput,
+        LppType::DigitalOutput,
+        LppType::AnalogInput,
+        LppType::AnalogOutput,
+        LppType::GenericSensor,
+        LppType::Luminosity,
+        LppType::Presence,
+        LppType::Temperature,
+        LppType::RelativeHumidity,
+        LppType::Accelerometer,
+        LppType::BarometricPressure,
+        LppType::Voltage,
+        LppType::Current,
+        LppType::Frequency,
+        LppType::Percentage,
+        LppType::Altitude,
+        LppType::Concentration,
+        LppType::Power,
+        LppType::Distance,
+        LppType::Energy,
+        LppType::Direction,
+        LppType::UnixTime,
+        LppType::Gyrometer,
+        LppType::Color,
+        LppType::GPS,
+        LppType::Switch,
+    ];
+
+    /// Returns the data type with the given type code, or `None` if the type code is not handled by this library.
+    #[inline]
+    pub const fn from_code(type_code: u8) -> Option<LppType> {
+        let type_codes: &[Option<LppType>; 256] = &TYPE_CODES;
+        type_codes[type_code as usize]
+    }
+
+    /// Returns the metadata of the data type from [`LPP_TYPES`].
+    #[inline]
+    pub const fn info(self) -> LppTypeInfo {
+        // The tables are borrowed, so they are promoted to statics
+        // instead of being copied for every lookup at runtime.
+        let lpp_types: &[LppTypeInfo; 26] = &LPP_TYPES;
+        lpp_types[self.index()]
+    }
+
+    /// Returns the index of the data type in [`LPP_TYPES`].
+    #[inline]
+    pub(crate) const fn index(self) -> usize {
+        let type_index: &[u8; 256] = &TYPE_INDEX;
+        type_index[self.code() as usize] as usize
+    }
+
+    /// Returns the metadata of the value with the given index in the payload of the data type, e.g., 0 for the x axis
+    /// of an accelerometer, or `None` if the data type has no value with this index.
+    pub const fn field(self, index: usize) -> Option<&'static LppField> {
+        let fields = self.info().fields;
+        if index < fields.len() {
+            Some(&fields[index])
+        } else {
+            None
+        }
+    }
+
+    /// Returns the type code of the data type in the payload.
+    #[inline]
+    pub const fn code(self) -> u8 {
+        self as u8
+    }
+
+    /// Returns the size of a packet of the data type including channel and data type.
+    #[inline]
+    pub const fn size(self) -> usize {
+        let type_sizes: &[u8; 256] = &TYPE_SIZES;
+        type_sizes[self.code() as usize] as usize
+    }
+
+    /// Returns the name of the data type in snake case, e.g., `relative_humidity`.
+    pub const fn name(self) -> &'static str {
+        self.info().name
+    }
+
+    /// Returns the unit of the values of the data type in [`CayenneLPPValue`](crate::CayenneLPPValue), or an empty
+    /// string if the values have no unit. For GPS values, the unit of the latitude and longitude is returned, the
+    /// units of all values are contained in [`LppTypeInfo::fields`].
+    pub const fn unit(self) -> &'static str {
+        self.info().fields[0].unit
+    }
+
+    /// Returns the resolution of the data type, i.e., the physical value of a single step of the raw value in the
+    /// payload (e.g., 0.1 for a temperature with a resolution of 0.1 °C). For GPS values, the resolution of the latitude
+    /// and longitude is returned, the resolutions of all values are contained in [`LppTypeInfo::fields`].
+    pub const fn resolution(self) -> f32 {
+        self.info().fields[0].resolution()
+    }
+}
+
+/// Metadata of a single value in the payload of a data type, e.g., the x axis of an accelerometer.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct LppField {
+    /// Name of the value, e.g., `value` for data types with a single value or `latitude` for GPS values
+    pub name: &'static str,
+
+    /// Number of bytes of the raw value in the payload
+    pub bytes: usize,
+
+    /// Whether the raw value is a signed integer
+    pub signed: bool,
+
+    /// Number of raw steps per unit, i.e., the inverse of the resolution (e.g., 10 for a resolution of 0.1 °C)
+    pub factor: f32,
+
+    /// Smallest raw value that is representable by the payload
+    pub raw_min: i64,
+
+    /// Largest raw value that is representable by the payload
+    pub raw_max: i64,
+
+    /// Whether the decoder rejects raw values outside of `raw_min..=raw_max`. Other ranges are only checked by the
+    /// encoders, e.g., a percentage above 100 is decoded as it is.
+    pub checked: bool,
+
+    /// Unit of the physical value, or an empty string if the value has no unit
+    pub unit: &'static str
+}
+
+impl LppField {
+    /// Creates an unsigned value that can use the full range of its bytes.
+    const fn unsigned(name: &'static str, bytes: usize, factor: f32, unit: &'static str) -> Self {
+        LppField { name, bytes, signed: false, factor, raw_min: 0, raw_max: (1 << (8 * bytes)) - 1, checked: false, unit }
+    }
+
+    /// Creates a signed value that can use the full range of its bytes.
+    const fn signed(name: &'static str, bytes: usize, factor: f32, unit: &'static str) -> Self {
+        let raw_max = (1 << (8 * bytes - 1)) - 1;
+        LppField { name, bytes, signed: true, factor, raw_min: -raw_max - 1, raw_max, checked: false, unit }
+    }
+
+    /// Restricts the raw values of the value to the range `raw_min..=raw_max`.
+    const fn with_range(self, raw_min: i64, raw_max: i64) -> Self {
+        LppField { raw_min, raw_max, ..self }
+    }
+
+    /// Lets the decoder reject raw values outside of the range of the value.
+    const fn checked(self) -> Self {
+        LppField { checked: true, ..self }
+    }
+
+    /// Returns whether the raw value is representable by the payload.
+    pub const fn contains(&self, raw: i64) -> bool {
+        self.raw_min <= raw && raw <= self.raw_max
+    }
+
+    /// Reads the raw value from the big-endian bytes of the field in the payload. Signed values are sign extended
+    /// from the number of bytes of the field.
+    #[inline(always)]
+    pub(crate) fn read(&self, bytes: &[u8]) -> i64 {
+        let raw = bytes.iter().fold(0, |raw, &byte| (raw << 8) | i64::from(byte));
+        if self.signed {
+            let shift = 64 - 8 * self.bytes;
+            (raw << shift) >> shift
+        } else {
+            raw
+        }
+    }
+
+    /// Returns the physical value of a single step of the raw value.
+    pub const fn resolution(&self) -> f32 {
+        1.0 / self.factor
+    }
+
+    /// Returns the smallest physical value that is representable by the payload.
+    pub const fn min(&self) -> f32 {
+        self.raw_min as f32 / self.factor
+    }
+
+    /// Returns the largest physical value that is representable by the payload.
+    pub const fn max(&self) -> f32 {
+        self.raw_max as f32 / self.factor
+    }
+}
+
+/// Metadata of a data type, see [`LPP_TYPES`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct LppTypeInfo {
+    /// The data type
+    pub lpp_type: LppType,
+
+    /// Name of the data type in snake case
+    pub name: &'static str,
+
+    /// The values of the data type in the order of the payload
+    pub fields: &'static [LppField]
+}
+
+impl LppTypeInfo {
+    /// Returns the size of a packet of the data type including channel and data type.
+    pub const fn size(&self) -> usize {
+        let mut size = 2;
+        let mut index = 0;
+        while index < self.fields.len() {
+            size += self.fields[index].bytes;
+            index += 1;
+        }
+
+        size
+    }
+}
+
+/// Largest number of fields of a data type in [`LPP_TYPES`]
+pub(crate) const MAX_FIELDS: usize = 3;
+
+/// Metadata of all data types that are handled by this library, ordered by their type code. The encoders and the
+/// decoder use this table for the sizes, the scaling and the range checks of the values, so it always describes the
+/// payload that is written and read by this library.
+pub const LPP_TYPES: [LppTypeInfo; 26] = [
+    LppTypeInfo {
+        lpp_type: LppType::DigitalInput,
+        name: "digital_input",
+        fields: &[LppField::unsigned("value", 1, 1.0, "")]
+    },
+    LppTypeInfo {
+        lpp_type: LppType::DigitalOutput,
+        name: "digital_output",
+        fields: &[LppField::unsigned("value", 1, 1.0, "")]
+    },
+    LppTypeInfo {
+        lpp_type: LppType::AnalogInput,
+        name: "analog_input",
+        fields: &[LppField::signed("value", 2, 100.0, "")]
+    },
+    LppTypeInfo {
+        lpp_type: LppType::AnalogOutput,
+        name: "analog_output",
+        fields: &[LppField::signed("value", 2, 100.0, "")]
+    },
+    LppTypeInfo {
+        lpp_type: LppType::GenericSensor,
+        name: "generic_sensor",
+        fields: &[LppField::unsigned("value", 4, 1.0, "")]
+    },
+    LppTypeInfo {
+        lpp_type: LppType::Luminosity,
+        name: "luminosity",
+        fields: &[LppField::unsigned("value", 2, 1.0, "lx")]
+    },
+    LppTypeInfo {
+        lpp_type: LppType::Presence,
+        name: "presence",
+        fields: &[LppField::unsigned("value", 1, 1.0, "")]
+    },
+    LppTypeInfo {
+        lpp_type: LppType::Temperature,
+        name: "temperature",
+        fields: &[LppField::signed("value", 2, 10.0, "°C")]
+    },
+    LppTypeInfo {
+        lpp_type: LppType::RelativeHumidity,
+        name: "relative_humidity",
+        fields: &[LppField::unsigned("value", 1, 2.0, "%")]
+    },
+    LppTypeInfo {
+        lpp_type: LppType::Accelerometer,
+        name: "accelerometer",
+        fields: &[
+            LppField::signed("x", 2, 1000.0, "G"),
+            LppField::signed("y", 2, 1000.0, "G"),
+            LppField::signed("z", 2, 1000.0, "G"),
+        ]
+    },
+    LppTypeInfo {
+        lpp_type: LppType::BarometricPressure,
+        name: "barometric_pressure",
+        fields: &[LppField::unsigned("value", 2, 10.0, "hPa")]
+    },
+    LppTypeInfo {
+        lpp_type: LppType::Voltage,
+        name: "voltage",
+        fields: &[LppField::unsigned("value", 2, 100.0, "V")]
+    },
+    LppTypeInfo {
+        lpp_type: LppType::Current,
+        name: "current",
+        fields: &[LppField::unsigned("value", 2, 1000.0, "A")]
+    },
+    LppTypeInfo {
+        lpp_type: LppType::Frequency,
+        name: "frequency",
+        fields: &[LppField::unsigned("value", 4, 1.0, "Hz")]
+    },
+    LppTypeInfo {
+        lpp_type: LppType::Percentage,
+        name: "percentage",
+        fields: &[LppField::unsigned("value", 1, 1.0, "%").with_range(0, 100)]
+    },
+    LppTypeInfo {
+        lpp_type: LppType::Altitude,
+        name: "altitude",
+        fields: &[LppField::signed("value", 2, 1.0, "m")]
+    },
+    LppTypeInfo {
+        lpp_type: LppType::Concentration,
+        name: "concentration",
+        fields: &[LppField::unsigned("value", 2, 1.0, "ppm")]
+    },
+    LppTypeInfo {
+        lpp_type: LppType::Power,
+        name: "power",
+        fields: &[LppField::unsigned("value", 2, 1.0, "W")]
+    },
+    LppTypeInfo {
+        lpp_type: LppType::Distance,
+        name: "distance",
+        fields: &[LppField::unsigned("value", 4, 1.0, "mm")]
+    },
+    LppTypeInfo {
+        lpp_type: LppType::Energy,
+        name: "energy",
+        fields: &[LppField::unsigned("value", 4, 1.0, "Wh")]
+    },
+    LppTypeInfo {
+        lpp_type: LppType::Direction,
+        name: "direction",
+        fields: &[LppField::unsigned("value", 2, 1.0, "°")]
+    },
+    LppTypeInfo {
+        lpp_type: LppType::UnixTime,
+        name: "unixtime",
+        fields: &[LppField::unsigned("value", 4, 1.0, "s")]
+    },
+    LppTypeInfo {
+        lpp_type: LppType::Gyrometer,
+        name: "gyrometer",
+        fields: &[
+            LppField::unsigned("x", 2, 100.0, "°/s"),
+            LppField::unsigned("y", 2, 100.0, "°/s"),
+            LppField::unsigned("z", 2, 100.0, "°/s"),
+        ]
+    },
+    LppTypeInfo {
+        lpp_type: LppType::Color,
+        name: "color",
+        fields: &[
+            LppField::unsigned("r", 1, 1.0, ""),
+            LppField::unsigned("g", 1, 1.0, ""),
+            LppField::unsigned("b", 1, 1.0, ""),
+        ]
+    },
+    LppTypeInfo {
+        lpp_type: LppType::GPS,
+        name: "gps",
+        fields: &[
+            LppField::signed("latitude", 3, 10_000.0, "°").with_range(-900_000, 900_000).checked(),
+            LppField::signed("longitude", 3, 10_000.0, "°").with_range(-1_800_000, 1_800_000).checked(),
+            LppField::signed("altitude", 3, 100.0, "m"),
+        ]
+    },
+    LppTypeInfo {
+        lpp_type: LppType::Switch,
+        name: "switch",
+        // the switch is written as 0 or 1, but every byte other than 0 is read as on
+        fields: &[LppField::unsigned("value", 1, 1.0, "")]
+    },
+];
+
+impl TryFrom<u8> for LppType {
+    type Error = Error;
+
+    /// Converts a type code into the data type. Type codes that are not handled by this library (e.g., custom types)
+    /// return `Error::UnhandledType`.
+    fn try_from(type_code: u8) -> Result<Self, Self::Error> {
+        LppType::from_code(type_code).ok_or(Error::UnhandledType(type_code))
+    }
+}
+
+impl From<LppType> for u8 {
+    fn from(lpp_type: LppType) -> u8 {
+        lpp_type.code()
+    }
+}
+
+/// Index of every type code in [`LPP_TYPES`], or `u8::MAX` if the type code is not handled by this library
+const TYPE_INDEX: [u8; 256] = {
+    let mut type_index = [u8::MAX; 256];
+    let mut index = 0;
+    while index < LPP_TYPES.len() {
+        type_index[LPP_TYPES[index].lpp_type.code() as usize] = index as u8;
+        index += 1;
+    }
+
+    type_index
+};
+
+/// Data type of every type code, or `None` if the type code is not handled by this library
+const TYPE_CODES: [Option<LppType>; 256] = {
+    let mut type_codes = [None; 256];
+    let mut index = 0;
+    while index < LPP_TYPES.len() {
+        type_codes[LPP_TYPES[index].lpp_type.code() as usize] = Some(LPP_TYPES[index].lpp_type);
+        index += 1;
+    }
+
+    type_codes
+};
+
+/// Size of every type code in [`LPP_TYPES`] including channel and data type, so the size does not have to be summed
+/// up from the fields for every entry of a payload
+const TYPE_SIZES: [u8; 256] = {
+    let mut type_sizes = [0; 256];
+    let mut index = 0;
+    while index < LPP_TYPES.len() {
+        type_sizes[LPP_TYPES[index].lpp_type.code() as usize] = LPP_TYPES[index].size() as u8;
+        index += 1;
+    }
+
+    type_sizes
+};
@@ -0,0 +1,88 @@
+use crate::error::Error;
+use crate::rounding::Rounding;
+
+/// Policy for values that exceed the range that is representable by the payload.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Overflow {
+    /// The value is rejected with `Error::OutOfRange`
+    #[default]
+    Reject,
+
+    /// The value is clamped to the nearest representable value (e.g., a pressure of 7000 hPa is sent as 6553.5 hPa).
+    /// This is closest to the behavior before the overflow policy was introduced: the floating point values were
+    /// converted with `as` casts, which saturate at the limits of the payload (only the altitude of a GPS position
+    /// wrapped around).
+    Clamp,
+
+    /// Only the bits that fit into the payload are written, i.e., the scaled value wraps around like an `as` cast
+    /// between integers. Bounds that are not given by the size of the payload (e.g., the latitude of a GPS position or
+    /// a percentage) are not checked.
+    Wrap,
+}
+
+impl Overflow {
+    /// Applies the overflow policy to a raw value that shall be in the range `min..=max` of the payload.
+    #[inline(always)]
+    pub(crate) fn limit(self, value: i64, min: i64, max: i64) -> Result<(i64, Encoded), Error> {
+        if (min..=max).contains(&value) {
+            return Ok((value, Encoded::Exact));
+        }
+
+        match self {
+            Overflow::Reject => Err(Error::OutOfRange),
+            Overflow::Clamp => Ok((value.clamp(min, max), Encoded::Clamped)),
+            Overflow::Wrap => Ok((value, Encoded::Wrapped)),
+        }
+    }
+
+    /// Applies the overflow policy to a raw value of a custom type, whose encoder only accepts values in the range
+    /// `min..=max`. In contrast to `limit()`, wrapped values are reduced to this range before they are written.
+    pub(crate) fn limit_custom(self, value: i64, min: i64, max: i64) -> Result<(i64, Encoded), Error> {
+        let (value, encoded) = self.limit(value, min, max)?;
+        if encoded != Encoded::Wrapped {
+            return Ok((value, encoded));
+        }
+
+        let span = max as i128 - min as i128 + 1;
+        Ok(((min as i128 + (value as i128 - min as i128).rem_euclid(span)) as i64, encoded))
+    }
+
+    /// Scales the value to the integer units of the payload, using the given rounding policy. Values that are not
+    /// representable by the range `min..=max` of the payload are handled by the overflow policy. NaN is always
+    /// rejected, as well as infinite values that cannot be clamped.
+    #[inline(always)]
+    pub(crate) fn scale(self, value: f32, factor: f32, rounding: Rounding, min: i64, max: i64)
+        -> Result<(i64, Encoded), Error> {
+        let scaled = rounding.round(value * factor);
+        if scaled.is_nan() || (scaled.is_infinite() && self != Overflow::Clamp) {
+            return Err(Error::OutOfRange);
+        }
+
+        // the cast saturates infinite values, which are clamped afterwards
+        self.limit(scaled as i64, min, max)
+    }
+}
+
+/// Describes how a value has been written to the payload.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Encoded {
+    /// The value is within the range of the payload and has been written as given (apart from rounding)
+    Exact,
+
+    /// The value exceeded the range of the payload and has been clamped (see [`Overflow::Clamp`])
+    Clamped,
+
+    /// The value exceeded the range of the payload and has been wrapped (see [`Overflow::Wrap`])
+    Wrapped,
+}
+
+impl Encoded {
+    /// Combines the results of values that are written to the same entry (e.g., the axes of an accelerometer).
+    /// The entry is only exact if all values are exact.
+    pub(crate) fn and(self, other: Encoded) -> Encoded {
+        match self {
+            Encoded::Exact => other,
+            _ => self,
+        }
+    }
+}
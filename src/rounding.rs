@@ -0,0 +1,46 @@
+/// Rounding policy that is used to convert floating point values to the integer units of the payload.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Rounding {
+    /// Rounds toward zero, i.e., the fractional part is cut off (25.49 °C becomes 25.4 °C)
+    Truncate,
+
+    /// Rounds to the nearest value, ties are rounded away from zero (25.45 °C becomes 25.5 °C)
+    #[default]
+    HalfAwayFromZero,
+
+    /// Rounds to the nearest value, ties are rounded to the even value (25.45 °C becomes 25.4 °C)
+    HalfEven,
+}
+
+impl Rounding {
+    /// Rounds the value to an integer according to the policy.
+    ///
+    /// Values that are not finite or that are too big to have a fractional part are returned unchanged.
+    pub fn round(self, value: f32) -> f32 {
+        // Every f32 with a magnitude of at least 2^23 is an integer already.
+        // This also filters NaN, because all comparisons with it are false.
+        if !(value > -8_388_608.0 && value < 8_388_608.0) {
+            return value;
+        }
+
+        let whole_number = value as i32;
+        let fraction = value - whole_number as f32;
+        let is_odd = whole_number % 2 != 0;
+
+        let rounded = match self {
+            Rounding::Truncate => whole_number,
+            Rounding::HalfAwayFromZero => match fraction {
+                f if f >= 0.5 => whole_number + 1,
+                f if f <= -0.5 => whole_number - 1,
+                _ => whole_number,
+            },
+            Rounding::HalfEven => match fraction {
+                f if f > 0.5 || (f == 0.5 && is_odd) => whole_number + 1,
+                f if f < -0.5 || (f == -0.5 && is_odd) => whole_number - 1,
+                _ => whole_number,
+            },
+        };
+
+        rounded as f32
+    }
+}
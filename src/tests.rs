@@ -239,16 +239,15 @@ fn add_relative_humidity_ok() {
 
 #[test]
 fn add_relative_humidity_negatives() {
-    // While it may seem nonsense to have negative relative humidities, it's tecnically
-    // possible with the code, so we should make sure that the math is at least right.
+    // Negative relative humidities are not representable by the payload, so they are rejected
+    // instead of silently being written as 0%.
     let mut buffer: [u8; 2 * LPP_RELATIVE_HUMIDITY_SIZE] = [0; 2 * LPP_RELATIVE_HUMIDITY_SIZE];
     let mut lpp = CayenneLPP::new(&mut buffer);
 
-    lpp.add_relative_humidity(2, -12.34).unwrap();
-    lpp.add_relative_humidity(3, -34.56).unwrap();
+    assert_eq!(Err(Error::OutOfRange), lpp.add_relative_humidity(2, -12.34));
+    assert_eq!(Err(Error::OutOfRange), lpp.add_relative_humidity(3, -34.56));
 
-    let expected_bytes: [u8; 6] = [0x02, LPP_RELATIVE_HUMIDITY, 0x00, 0x03, LPP_RELATIVE_HUMIDITY, 0x00];
-    assert_eq!(expected_bytes, buffer);
+    assert!(lpp.payload_slice().is_empty());
 }
 
 #[test]
@@ -282,8 +281,8 @@ fn ass_accelerometer_overflow() {
     let mut buffer: [u8; LPP_ACCELEROMETER_SIZE + 2] = [0; LPP_ACCELEROMETER_SIZE + 2];
     let mut lpp = CayenneLPP::new(&mut buffer);
 
-    lpp.add_accelerometer(3, 27.2, 34.2, 56.1).unwrap();
-    let result = lpp.add_accelerometer(5, 25.5, 98.1, 23.5);
+    lpp.add_accelerometer(3, 2.72, 3.42, 5.61).unwrap();
+    let result = lpp.add_accelerometer(5, 2.55, 9.81, 2.35);
 
     assert_eq!(Err(Error::InsufficientMemory), result);
 }
@@ -320,7 +319,7 @@ fn add_voltage() {
     lpp.add_voltage(5,   7.890).unwrap();
 
     let expected_bytes = [
-        0x03, LPP_VOLTAGE, 0x30, 0x39,
+        0x03, LPP_VOLTAGE, 0x30, 0x3A,
         0x05, LPP_VOLTAGE, 0x03, 0x15
     ];
 
@@ -357,7 +356,7 @@ fn add_current() {
     lpp.add_current(5,  0.7890).unwrap();
 
     let expected_bytes = [
-        0x03, LPP_CURRENT, 0x30, 0x39,
+        0x03, LPP_CURRENT, 0x30, 0x3A,
         0x05, LPP_CURRENT, 0x03, 0x15
     ];
 
@@ -612,7 +611,7 @@ fn add_gyrometer_ok() {
 
     let expected_bytes: [u8; 16] = [
         0x06, LPP_GYROMETER, 0x04, 0xD2, 0x16, 0x2E, 0x03, 0x84,
-        0x03, LPP_GYROMETER, 0x19, 0x1A, 0x0C, 0x39, 0x0B, 0x25
+        0x03, LPP_GYROMETER, 0x19, 0x1B, 0x0C, 0x39, 0x0B, 0x25
     ];
     assert_eq!(expected_bytes, buffer);
 }
@@ -744,4 +743,392 @@ fn add_color_overflow() {
     let result = lpp.add_color(5, 0x78, 0x9a, 0xbc);
 
     assert_eq!(Err(Error::InsufficientMemory), result);
-}
\ No newline at end of file
+}
+#[test]
+fn add_raw_values_ok() {
+    let mut buffer = [0u8;
+        LPP_ANALOG_INPUT_SIZE +
+        LPP_ANALOG_OUTPUT_SIZE +
+        LPP_TEMPERATURE_SIZE +
+        LPP_RELATIVE_HUMIDITY_SIZE +
+        LPP_ACCELEROMETER_SIZE +
+        LPP_BAROMETRIC_PRESSURE_SIZE +
+        LPP_VOLTAGE_SIZE +
+        LPP_CURRENT_SIZE +
+        LPP_GYROMETER_SIZE +
+        LPP_GPS_SIZE
+    ];
+    let mut lpp = CayenneLPP::new(&mut buffer);
+
+    // use the raw values of the unit tests for the floating point functions
+    lpp.add_analog_input_raw(3, 2720).unwrap();
+    lpp.add_analog_output_raw(5, 2550).unwrap();
+    lpp.add_temperature_raw(5, 255).unwrap();
+    lpp.add_relative_humidity_raw(3, 131).unwrap();
+    lpp.add_accelerometer_raw(3, 6427, 3129, -2853).unwrap();
+    lpp.add_barometric_pressure_raw(5, 9923).unwrap();
+    lpp.add_voltage_raw(3, 12345).unwrap();
+    lpp.add_current_raw(5, 12345).unwrap();
+    lpp.add_gyrometer_raw(6, 1234, 5678, 900).unwrap();
+    lpp.add_gps_raw(1, 423518, -879094, 1000).unwrap();
+
+    let expected_bytes = [
+        0x03, LPP_ANALOG_INPUT, 0x0A, 0xA0,
+        0x05, LPP_ANALOG_OUTPUT, 0x09, 0xF6,
+        0x05, LPP_TEMPERATURE, 0x00, 0xFF,
+        0x03, LPP_RELATIVE_HUMIDITY, 0x83,
+        0x03, LPP_ACCELEROMETER, 0x19, 0x1B, 0x0C, 0x39, 0xF4, 0xDB,
+        0x05, LPP_BAROMETRIC_PRESSURE, 0x26, 0xC3,
+        0x03, LPP_VOLTAGE, 0x30, 0x39,
+        0x05, LPP_CURRENT, 0x30, 0x39,
+        0x06, LPP_GYROMETER, 0x04, 0xD2, 0x16, 0x2E, 0x03, 0x84,
+        0x01, LPP_GPS, 0x06, 0x76, 0x5E, 0xF2, 0x96, 0x0A, 0x00, 0x03, 0xE8,
+    ];
+    assert_eq!(expected_bytes, buffer);
+}
+
+#[test]
+fn add_gps_raw_bounds() {
+    let mut buffer: [u8; LPP_GPS_SIZE] = [0; LPP_GPS_SIZE];
+    let mut lpp = CayenneLPP::new(&mut buffer);
+
+    assert_eq!(lpp.add_gps_raw(3,  900_001,     0,        0), Err(Error::OutOfRange));
+    assert_eq!(lpp.add_gps_raw(3, -900_001,     0,        0), Err(Error::OutOfRange));
+    assert_eq!(lpp.add_gps_raw(3, 0,    1_800_001,        0), Err(Error::OutOfRange));
+    assert_eq!(lpp.add_gps_raw(3, 0,   -1_800_001,        0), Err(Error::OutOfRange));
+    assert_eq!(lpp.add_gps_raw(3, 0,            0, 0x80_0000), Err(Error::OutOfRange));
+    assert_eq!(lpp.add_gps_raw(3, 0,            0, -0x80_0001), Err(Error::OutOfRange));
+
+    lpp.add_gps_raw(3, 900_000, -1_800_000, -0x80_0000).unwrap();
+}
+
+#[test]
+fn rounding_round() {
+    assert_eq!(254.0, Rounding::Truncate.round(254.9));
+    assert_eq!(-254.0, Rounding::Truncate.round(-254.9));
+
+    assert_eq!(255.0, Rounding::HalfAwayFromZero.round(254.5));
+    assert_eq!(254.0, Rounding::HalfAwayFromZero.round(254.4));
+    assert_eq!(-255.0, Rounding::HalfAwayFromZero.round(-254.5));
+    assert_eq!(-254.0, Rounding::HalfAwayFromZero.round(-254.4));
+
+    assert_eq!(254.0, Rounding::HalfEven.round(254.5));
+    assert_eq!(256.0, Rounding::HalfEven.round(255.5));
+    assert_eq!(255.0, Rounding::HalfEven.round(254.6));
+    assert_eq!(-254.0, Rounding::HalfEven.round(-254.5));
+    assert_eq!(-256.0, Rounding::HalfEven.round(-255.5));
+    assert_eq!(-255.0, Rounding::HalfEven.round(-254.6));
+
+    // values without a fractional part and NaN are not changed
+    assert_eq!(1.0e10, Rounding::HalfEven.round(1.0e10));
+    assert!(Rounding::HalfAwayFromZero.round(f32::NAN).is_nan());
+}
+
+#[test]
+fn add_temperature_rounding() {
+    let mut buffer: [u8; 3 * LPP_TEMPERATURE_SIZE] = [0; 3 * LPP_TEMPERATURE_SIZE];
+
+    let mut lpp = CayenneLPP::new(&mut buffer).with_rounding(Rounding::Truncate);
+    lpp.add_temperature(3, 25.49).unwrap();
+    lpp.add_temperature(3, -25.49).unwrap();
+    lpp.add_temperature(3, 25.25).unwrap();
+    assert_eq!([0x00, 0xFE, 0xFF, 0x02, 0x00, 0xFC], [buffer[2], buffer[3], buffer[6], buffer[7], buffer[10], buffer[11]]);
+
+    let mut lpp = CayenneLPP::new(&mut buffer);
+    assert_eq!(Rounding::HalfAwayFromZero, lpp.rounding());
+    lpp.add_temperature(3, 25.49).unwrap();
+    lpp.add_temperature(3, -25.49).unwrap();
+    lpp.add_temperature(3, 25.25).unwrap();
+    assert_eq!([0x00, 0xFF, 0xFF, 0x01, 0x00, 0xFD], [buffer[2], buffer[3], buffer[6], buffer[7], buffer[10], buffer[11]]);
+
+    let mut lpp = CayenneLPP::new(&mut buffer).with_rounding(Rounding::HalfEven);
+    lpp.add_temperature(3, 25.49).unwrap();
+    lpp.add_temperature(3, -25.49).unwrap();
+    lpp.add_temperature(3, 25.25).unwrap();
+    assert_eq!([0x00, 0xFF, 0xFF, 0x01, 0x00, 0xFC], [buffer[2], buffer[3], buffer[6], buffer[7], buffer[10], buffer[11]]);
+}
+
+#[test]
+fn add_values_out_of_range() {
+    let mut buffer = [0u8; LPP_GPS_SIZE];
+    let mut lpp = CayenneLPP::new(&mut buffer);
+
+    // the limits of the payload are accepted...
+    lpp.add_temperature(3, 3276.7).unwrap();
+    lpp.reset();
+    lpp.add_temperature(3, -3276.8).unwrap();
+    lpp.reset();
+    lpp.add_relative_humidity(3, 127.5).unwrap();
+    lpp.reset();
+    lpp.add_barometric_pressure(3, 6553.5).unwrap();
+    lpp.reset();
+    lpp.add_accelerometer(3, 32.767, -32.768, 0.0).unwrap();
+    lpp.reset();
+    lpp.add_percentage(3, 100).unwrap();
+    lpp.reset();
+
+    // ...but values beyond are rejected
+    assert_eq!(Err(Error::OutOfRange), lpp.add_analog_input(3, 327.68));
+    assert_eq!(Err(Error::OutOfRange), lpp.add_analog_output(3, -327.69));
+    assert_eq!(Err(Error::OutOfRange), lpp.add_temperature(5, 5000.0));
+    assert_eq!(Err(Error::OutOfRange), lpp.add_temperature(5, -3276.9));
+    assert_eq!(Err(Error::OutOfRange), lpp.add_relative_humidity(5, 128.0));
+    assert_eq!(Err(Error::OutOfRange), lpp.add_accelerometer(5, 32.768, 0.0, 0.0));
+    assert_eq!(Err(Error::OutOfRange), lpp.add_accelerometer(5, 0.0, -32.769, 0.0));
+    assert_eq!(Err(Error::OutOfRange), lpp.add_accelerometer(5, 0.0, 0.0, 40.0));
+    assert_eq!(Err(Error::OutOfRange), lpp.add_barometric_pressure(5, 6553.6));
+    assert_eq!(Err(Error::OutOfRange), lpp.add_barometric_pressure(5, -1.0));
+    assert_eq!(Err(Error::OutOfRange), lpp.add_voltage(5, -0.1));
+    assert_eq!(Err(Error::OutOfRange), lpp.add_current(5, -0.1));
+    assert_eq!(Err(Error::OutOfRange), lpp.add_gyrometer(5, -1.0, 0.0, 0.0));
+    assert_eq!(Err(Error::OutOfRange), lpp.add_gyrometer(5, 0.0, 655.36, 0.0));
+    assert_eq!(Err(Error::OutOfRange), lpp.add_gps(5, 45.0, 90.0, 83_886.08));
+    assert_eq!(Err(Error::OutOfRange), lpp.add_percentage(5, 200));
+
+    // nothing has been written by the rejected values
+    assert!(lpp.payload_slice().is_empty());
+}
+
+#[test]
+fn add_values_clamp() {
+    let mut buffer = [0u8; 64];
+    let mut lpp = CayenneLPP::new(&mut buffer).with_overflow(Overflow::Clamp);
+    assert_eq!(Overflow::Clamp, lpp.overflow());
+
+    assert_eq!(Ok(Encoded::Exact), lpp.add_temperature(1, 21.5));
+    assert_eq!(Ok(Encoded::Clamped), lpp.add_temperature(2, 5000.0));
+    assert_eq!(Ok(Encoded::Clamped), lpp.add_barometric_pressure(3, -1.0));
+    assert_eq!(Ok(Encoded::Clamped), lpp.add_relative_humidity(4, 200.0));
+    assert_eq!(Ok(Encoded::Clamped), lpp.add_accelerometer(5, 0.0, 1.0, -40.0));
+    assert_eq!(Ok(Encoded::Clamped), lpp.add_gps(6, 100.0, 0.0, 0.0));
+    assert_eq!(Ok(Encoded::Clamped), lpp.add_percentage(7, 200));
+    assert_eq!(Ok(Encoded::Clamped), lpp.add_temperature(8, f32::INFINITY));
+
+    // NaN can not be clamped
+    assert_eq!(Err(Error::OutOfRange), lpp.add_temperature(9, f32::NAN));
+
+    let expected_bytes: [u8; 41] = [
+        0x01, LPP_TEMPERATURE, 0x00, 0xD7,
+        0x02, LPP_TEMPERATURE, 0x7F, 0xFF,
+        0x03, LPP_BAROMETRIC_PRESSURE, 0x00, 0x00,
+        0x04, LPP_RELATIVE_HUMIDITY, 0xFF,
+        0x05, LPP_ACCELEROMETER, 0x00, 0x00, 0x03, 0xE8, 0x80, 0x00,
+        0x06, LPP_GPS, 0x0D, 0xBB, 0xA0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x07, LPP_PERCENTAGE, 0x64,
+        0x08, LPP_TEMPERATURE, 0x7F, 0xFF
+    ];
+    assert_eq!(&expected_bytes, lpp.payload_slice());
+}
+
+#[test]
+fn add_values_wrap() {
+    let mut buffer = [0u8; 64];
+    let mut lpp = CayenneLPP::new(&mut buffer).with_overflow(Overflow::Wrap);
+
+    assert_eq!(Ok(Encoded::Exact), lpp.add_voltage(1, 0.5));
+    assert_eq!(Ok(Encoded::Wrapped), lpp.add_temperature(2, 3276.8));
+    assert_eq!(Ok(Encoded::Wrapped), lpp.add_barometric_pressure(3, 6553.6));
+    assert_eq!(Ok(Encoded::Wrapped), lpp.add_voltage(4, -0.01));
+    assert_eq!(Ok(Encoded::Wrapped), lpp.add_percentage(5, 200));
+
+    // infinite values can not be wrapped
+    assert_eq!(Err(Error::OutOfRange), lpp.add_temperature(6, f32::INFINITY));
+    assert_eq!(Err(Error::OutOfRange), lpp.add_temperature(6, f32::NAN));
+
+    let expected_bytes: [u8; 19] = [
+        0x01, LPP_VOLTAGE, 0x00, 0x32,
+        0x02, LPP_TEMPERATURE, 0x80, 0x00,
+        0x03, LPP_BAROMETRIC_PRESSURE, 0x00, 0x00,
+        0x04, LPP_VOLTAGE, 0xFF, 0xFF,
+        0x05, LPP_PERCENTAGE, 0xC8
+    ];
+    assert_eq!(&expected_bytes, lpp.payload_slice());
+}
+
+#[test]
+fn add_values_not_finite() {
+    let mut buffer = [0u8; LPP_GPS_SIZE];
+    let mut lpp = CayenneLPP::new(&mut buffer);
+
+    for value in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+        assert_eq!(Err(Error::OutOfRange), lpp.add_analog_input(3, value));
+        assert_eq!(Err(Error::OutOfRange), lpp.add_analog_output(3, value));
+        assert_eq!(Err(Error::OutOfRange), lpp.add_temperature(3, value));
+        assert_eq!(Err(Error::OutOfRange), lpp.add_relative_humidity(3, value));
+        assert_eq!(Err(Error::OutOfRange), lpp.add_accelerometer(3, 0.0, value, 0.0));
+        assert_eq!(Err(Error::OutOfRange), lpp.add_barometric_pressure(3, value));
+        assert_eq!(Err(Error::OutOfRange), lpp.add_voltage(3, value));
+        assert_eq!(Err(Error::OutOfRange), lpp.add_current(3, value));
+        assert_eq!(Err(Error::OutOfRange), lpp.add_gyrometer(3, 0.0, 0.0, value));
+        assert_eq!(Err(Error::OutOfRange), lpp.add_gps(3, value, 0.0, 0.0));
+        assert_eq!(Err(Error::OutOfRange), lpp.add_gps(3, 0.0, value, 0.0));
+        assert_eq!(Err(Error::OutOfRange), lpp.add_gps(3, 0.0, 0.0, value));
+    }
+
+    assert!(lpp.payload_slice().is_empty());
+}
+
+#[test]
+fn lpp_size_lookup() {
+    const SIZE: usize = match lpp_size(LPP_GPS) {
+        Some(size) => size,
+        None => 0
+    };
+    assert_eq!(LPP_GPS_SIZE, SIZE);
+
+    assert_eq!(Some(LPP_DIGITAL_INPUT_SIZE), lpp_size(LPP_DIGITAL_INPUT));
+    assert_eq!(Some(LPP_TEMPERATURE_SIZE), lpp_size(LPP_TEMPERATURE));
+    assert_eq!(Some(LPP_ACCELEROMETER_SIZE), lpp_size(LPP_ACCELEROMETER));
+    assert_eq!(Some(LPP_SWITCH_SIZE), lpp_size(LPP_SWITCH));
+    assert_eq!(None, lpp_size(0xC8));
+}
+
+#[test]
+fn value_encoded_size() {
+    let values = [
+        CayenneLPPValue::DigitalInput(1),
+        CayenneLPPValue::Temperature(21.5),
+        CayenneLPPValue::Accelerometer(1.0, 2.0, 3.0),
+        CayenneLPPValue::GPS(42.3519, -87.9094, 10.0),
+        CayenneLPPValue::Color(1, 2, 3),
+        CayenneLPPValue::Switch(true),
+    ];
+
+    // the size matches the bytes that are written to the payload
+    for value in values {
+        let mut buffer = [0u8; 16];
+        let mut lpp = CayenneLPP::new(&mut buffer);
+        lpp.add_scalar(&CayenneLPPScalar { channel: 1, value }).unwrap();
+        assert_eq!(Some(lpp.payload_slice().len()), value.encoded_size());
+    }
+
+    assert_eq!(None, CayenneLPPValue::Custom(0xC8, 1.0).encoded_size());
+}
+
+#[test]
+fn lpp_type_metadata() {
+    // the type codes and sizes of the Cayenne LPP specification
+    assert_eq!(0, LPP_DIGITAL_INPUT);
+    assert_eq!(103, LPP_TEMPERATURE);
+    assert_eq!(136, LPP_GPS);
+    assert_eq!(142, LPP_SWITCH);
+    assert_eq!(3, LPP_PRESENCE_SIZE);
+    assert_eq!(4, LPP_TEMPERATURE_SIZE);
+    assert_eq!(11, LPP_GPS_SIZE);
+
+    assert_eq!(LPP_RELATIVE_HUMIDITY, LppType::RelativeHumidity.code());
+    assert_eq!(LPP_RELATIVE_HUMIDITY_SIZE, LppType::RelativeHumidity.size());
+    assert_eq!("relative_humidity", LppType::RelativeHumidity.name());
+    assert_eq!("%", LppType::RelativeHumidity.unit());
+    assert_eq!(0.5, LppType::RelativeHumidity.resolution());
+    assert_eq!("°C", LppType::Temperature.unit());
+    assert_eq!(0.1, LppType::Temperature.resolution());
+
+    for lpp_type in LppType::ALL {
+        assert_eq!(Ok(lpp_type), LppType::try_from(lpp_type.code()));
+        assert_eq!(lpp_type.code(), u8::from(lpp_type));
+        assert_eq!(Some(lpp_type.size()), lpp_size(lpp_type.code()));
+    }
+    assert!(LppType::ALL.windows(2).all(|types| types[0].code() < types[1].code()));
+
+    assert_eq!(Err(Error::UnhandledType(0xC8)), LppType::try_from(0xC8));
+    assert_eq!(None, LppType::from_code(4));
+}
+
+#[test]
+fn value_kind() {
+    assert_eq!(Some(LppType::Temperature), CayenneLPPValue::Temperature(21.5).kind());
+    assert_eq!(Some(LppType::GPS), CayenneLPPValue::GPS(42.3519, -87.9094, 10.0).kind());
+    assert_eq!(Some(LppType::Presence), CayenneLPPValue::Presence(1).kind());
+    assert_eq!(None, CayenneLPPValue::Custom(0xC8, 1.0).kind());
+}
+
+#[test]
+fn lpp_types_table() {
+    let temperature = LppType::Temperature.info();
+    assert_eq!("temperature", temperature.name);
+    assert_eq!(LPP_TEMPERATURE_SIZE, temperature.size());
+    assert_eq!(1, temperature.fields.len());
+    assert!(temperature.fields[0].signed);
+    assert_eq!(-3276.8, temperature.fields[0].min());
+    assert_eq!(3276.7, temperature.fields[0].max());
+
+    let relative_humidity = LppType::RelativeHumidity.field(0).unwrap();
+    assert!(!relative_humidity.signed);
+    assert_eq!((0.0, 127.5), (relative_humidity.min(), relative_humidity.max()));
+    assert_eq!(None, LppType::RelativeHumidity.field(1));
+
+    let gps = LppType::GPS.info();
+    assert_eq!(["latitude", "longitude", "altitude"], [gps.fields[0].name, gps.fields[1].name, gps.fields[2].name]);
+    assert_eq!((-90.0, 90.0), (gps.fields[0].min(), gps.fields[0].max()));
+    assert_eq!((-180.0, 180.0), (gps.fields[1].min(), gps.fields[1].max()));
+    assert_eq!("m", gps.fields[2].unit);
+    assert_eq!(0.01, gps.fields[2].resolution());
+
+    // the table is ordered like LppType::ALL and matches the sizes of the payload
+    for (info, lpp_type) in LPP_TYPES.iter().zip(LppType::ALL) {
+        assert_eq!(lpp_type, info.lpp_type);
+        assert_eq!(info.size(), lpp_type.size());
+        assert!(info.fields.iter().all(|field| field.raw_min < field.raw_max));
+    }
+
+    // the encoders accept the range of the table, but nothing beyond
+    let mut buffer = [0u8; 32];
+    let mut lpp = CayenneLPP::new(&mut buffer);
+    let pressure = LppType::BarometricPressure.field(0).unwrap();
+    assert_eq!(Ok(Encoded::Exact), lpp.add_barometric_pressure(1, pressure.max()));
+    assert_eq!(Err(Error::OutOfRange), lpp.add_barometric_pressure(1, pressure.max() + pressure.resolution()));
+    assert_eq!(Ok(Encoded::Exact), lpp.add_gps(2, gps.fields[0].min(), gps.fields[1].max(), 0.0));
+    assert_eq!(Err(Error::OutOfRange), lpp.add_gps(2, 90.001, 0.0, 0.0));
+    assert_eq!(Err(Error::OutOfRange), lpp.add_percentage(3, LppType::Percentage.field(0).unwrap().raw_max as u8 + 1));
+}
+
+#[test]
+fn lpp_types_round_trip() {
+    // every data type is written and read from the table, so the limits of all fields have to survive a round trip
+    for lpp_type in LppType::ALL {
+        let fields = lpp_type.info().fields;
+        for limit in [|field: &LppField| field.raw_min, |field: &LppField| field.raw_max] {
+            let mut payload = [0u8; 16];
+            payload[0] = 7;
+            payload[1] = lpp_type.code();
+            let mut size = 2;
+            for field in fields {
+                payload[size..size + field.bytes].copy_from_slice(&limit(field).to_be_bytes()[8 - field.bytes..]);
+                size += field.bytes;
+            }
+            let payload = &payload[..size];
+
+            let (value, read) = CayenneLPPRawValue::from_bytes(lpp_type, &payload[2..]).unwrap();
+            let value = value.unwrap();
+            assert_eq!(payload.len() - 2, read);
+            assert_eq!(Some(lpp_type), value.kind());
+
+            let mut buffer = [0u8; 16];
+            let mut lpp = CayenneLPP::new(&mut buffer);
+            assert_eq!(Ok(Encoded::Exact), lpp.add_raw_scalar(&CayenneLPPRawScalar { channel: 7, value }));
+            assert_eq!(Some(Ok(CayenneLPPRawScalar { channel: 7, value })), lpp.raw_iter().next());
+
+            // a switch is read as on for every byte other than 0, but always written as 1
+            if lpp_type != LppType::Switch {
+                assert_eq!(payload, lpp.payload_slice());
+            }
+
+            let scalar = lpp.iter().next().unwrap().unwrap();
+            let mut scalar_buffer = [0u8; 16];
+            let mut scalar_lpp = CayenneLPP::new(&mut scalar_buffer);
+            assert_eq!(Ok(Encoded::Exact), scalar_lpp.add_scalar(&scalar));
+            assert_eq!(lpp.payload_slice(), scalar_lpp.payload_slice());
+        }
+    }
+
+    // only the ranges of checked fields are enforced by the decoder, other values are decoded as they are
+    let payload = [1, LPP_PERCENTAGE, 101, 2, LPP_GPS, 0x7F, 0xFF, 0xFF, 0, 0, 0, 0, 0, 0];
+    let mut decoder = CayenneLPPDecoder::new(&payload).raw_iter();
+    let percentage = CayenneLPPRawScalar { channel: 1, value: CayenneLPPRawValue::Percentage(101) };
+    assert_eq!(Some(Ok(percentage)), decoder.next());
+    let kind = error::DecodeErrorKind::OutOfRange;
+    let error = error::DecodeError { offset: 3, channel: 2, type_code: Some(LPP_GPS), kind };
+    assert_eq!(Some(Err(Error::Decode(error))), decoder.next());
+    assert_eq!(None, decoder.next());
+}
@@ -1,5 +1,7 @@
 use cayenne_lpp::*;
-use crate::error::Error;
+use crate::error::{DecodeError, DecodeErrorKind, DispatchError, Error};
+#[cfg(feature = "ttn")]
+use crate::error::{JsonError, JsonErrorKind};
 
 #[test]
 fn test_all_possible_payloads() {
@@ -79,7 +81,7 @@ fn test_all_possible_payloads() {
         0x03, LPP_RELATIVE_HUMIDITY, 0x83,
         0x03, LPP_ACCELEROMETER, 0x19, 0x1B, 0x0C, 0x39, 0xF4, 0xDB,
         0x05, LPP_BAROMETRIC_PRESSURE, 0x26, 0xC3,
-        0x03, LPP_VOLTAGE, 0x30, 0x39,
+        0x03, LPP_VOLTAGE, 0x30, 0x3A,
         0x05, LPP_CURRENT, 0x30, 0x39,
         0x03, LPP_FREQUENCY, 0x36, 0x45, 0x82, 0x48,
         0x03, LPP_PERCENTAGE, 12,
@@ -193,7 +195,7 @@ fn test_scalar_and_iter() {
         lpp.add_scalar(&scalar).unwrap();
     }
     let mut count = 0;
-    for (example, result) in scalars.into_iter().zip(lpp.into_iter()) {
+    for (example, result) in scalars.into_iter().zip(lpp) {
         assert_eq!(Ok(example), result);
         count += 1;
     }
@@ -230,8 +232,18 @@ fn test_iter_buffer_underrun() {
     let mut _scalar = iter.next();
     _scalar = iter.next();
 
-    // the next call to next should be Err(Error:BufferUnderrun)
-    assert_eq!(iter.next(), Some(Err(Error::BufferUnderrun)));
+    // the next call to next should be a buffer underrun that
+    // points to the dangling packet
+    let expected = DecodeError {
+        offset: LPP_DIGITAL_INPUT_SIZE + LPP_DIGITAL_OUTPUT_SIZE,
+        channel: 0,
+        type_code: Some(LPP_DIGITAL_INPUT),
+        kind: DecodeErrorKind::BufferUnderrun { expected: LPP_DIGITAL_INPUT_SIZE, available: ADDITIONAL_BYTES }
+    };
+    assert_eq!(iter.next(), Some(Err(Error::Decode(expected))));
+
+    // nothing can be unpacked after the dangling packet
+    assert_eq!(iter.next(), None);
 }
 
 #[test]
@@ -252,8 +264,15 @@ fn test_iter_value_out_of_bounds_lat() {
 
     let lpp = CayenneLPP::new(&mut buffer);
 
+    let expected = DecodeError {
+        offset: 0,
+        channel: 0x01,
+        type_code: Some(LPP_GPS),
+        kind: DecodeErrorKind::OutOfRange
+    };
+
     let mut iter = lpp.into_iter();
-    assert_eq!(iter.next(), Some(Err(Error::OutOfRange)));
+    assert_eq!(iter.next(), Some(Err(Error::Decode(expected))));
 }
 
 #[test]
@@ -274,8 +293,15 @@ fn test_iter_value_out_of_bounds_lon() {
 
     let lpp = CayenneLPP::new(&mut buffer);
 
+    let expected = DecodeError {
+        offset: 0,
+        channel: 0x01,
+        type_code: Some(LPP_GPS),
+        kind: DecodeErrorKind::OutOfRange
+    };
+
     let mut iter = lpp.into_iter();
-    assert_eq!(iter.next(), Some(Err(Error::OutOfRange)));
+    assert_eq!(iter.next(), Some(Err(Error::Decode(expected))));
 }
 
 #[test]
@@ -289,8 +315,48 @@ fn test_iter_unhandled_type() {
 
     let lpp = CayenneLPP::new(&mut buffer);
     
+    let expected = DecodeError {
+        offset: 0,
+        channel: 4,
+        type_code: Some(4),
+        kind: DecodeErrorKind::UnhandledType
+    };
+
     let mut iter = lpp.into_iter();
-    assert_eq!(iter.next(), Some(Err(Error::UnhandledType(4))));
+    assert_eq!(iter.next(), Some(Err(Error::Decode(expected))));
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+fn test_iter_stray_byte() {
+    // a single byte at the end of the payload only contains a channel
+    let frame: &[u8] = &[0x03, LPP_DIGITAL_INPUT, 0x55, 0x07];
+
+    let expected = DecodeError {
+        offset: LPP_DIGITAL_INPUT_SIZE,
+        channel: 7,
+        type_code: None,
+        kind: DecodeErrorKind::BufferUnderrun { expected: 2, available: 1 }
+    };
+
+    let mut iter = CayenneLPPDecoder::new(frame).iter();
+    assert_eq!(iter.next(), Some(Ok(CayenneLPPScalar{ channel: 3, value: CayenneLPPValue::DigitalInput(0x55) })));
+    assert_eq!(iter.next(), Some(Err(Error::Decode(expected))));
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+fn test_iter_continues_after_out_of_range() {
+    // the GPS entry is complete, so the following entry can still be unpacked
+    let frame: &[u8] = &[
+        0x01, LPP_GPS, 0x0F, 0x42, 0x40, 0xF2, 0x96, 0x0A, 0x00, 0x03, 0xE8,
+        0x03, LPP_DIGITAL_INPUT, 0x55,
+    ];
+
+    let mut iter = CayenneLPPDecoder::new(frame).iter();
+    assert!(matches!(iter.next(), Some(Err(Error::Decode(DecodeError { kind: DecodeErrorKind::OutOfRange, .. })))));
+    assert_eq!(iter.next(), Some(Ok(CayenneLPPScalar{ channel: 3, value: CayenneLPPValue::DigitalInput(0x55) })));
+    assert_eq!(iter.next(), None);
 }
 
 #[test]
@@ -396,8 +462,7 @@ fn test_scalar_fmt_debug() {
     impl From<&str> for StrBuffer {
         fn from(s: &str) -> Self {
             let string_slice = s.as_bytes();
-            let mut retval = Self::default();
-            retval.0 = string_slice.len();
+            let mut retval = Self(string_slice.len(), [0u8; 128]);
             retval.1[..retval.0].copy_from_slice(string_slice);    
             retval        
         }
@@ -516,4 +581,1062 @@ fn test_scalar_fmt_debug() {
         write!(&mut formatted, "{:?}", result).expect("failed to format, buffer probably too small");
         assert_eq!(formatted, <&str as Into<StrBuffer>>::into(example))
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_decoder_iter() {
+    // The decoder works on an immutable slice, e.g., a frame that was received from a network server
+    let frame: &[u8] = &[
+        0x03, LPP_DIGITAL_INPUT, 0x55,
+        0x05, LPP_TEMPERATURE, 0x00, 0xFF,
+        0x01, LPP_GPS, 0x06, 0x76, 0x5E, 0xF2, 0x96, 0x0A, 0x00, 0x03, 0xE8,
+    ];
+
+    let scalars = [
+        CayenneLPPScalar{ channel: 3, value: CayenneLPPValue::DigitalInput(0x55) },
+        CayenneLPPScalar{ channel: 5, value: CayenneLPPValue::Temperature(25.5) },
+        CayenneLPPScalar{ channel: 1, value: CayenneLPPValue::GPS(42.3518, -87.9094, 10.0) },
+    ];
+
+    let decoder = CayenneLPPDecoder::new(frame);
+    assert_eq!(frame, decoder.payload_slice());
+
+    // failable iteration
+    let mut count = 0;
+    for (example, result) in scalars.into_iter().zip(decoder.iter()) {
+        assert_eq!(Ok(example), result);
+        count += 1;
+    }
+    assert_eq!(count, scalars.len());
+
+    // infailable iteration over the same decoder
+    let mut count = 0;
+    for (example, result) in scalars.into_iter().zip(decoder.infailable_iter()) {
+        assert_eq!(example, result);
+        count += 1;
+    }
+    assert_eq!(count, scalars.len());
+
+    // the decoder can also be consumed directly
+    assert_eq!(scalars.len(), decoder.into_iter().count());
+}
+
+#[test]
+fn test_decoder_infailable_iter_stops_on_error() {
+    let frame: &[u8] = &[
+        0x03, LPP_DIGITAL_INPUT, 0x55,
+        0x05, 0x04, 0x00,
+        0x05, LPP_TEMPERATURE, 0x00, 0xFF,
+    ];
+
+    let decoder = CayenneLPPDecoder::new(frame);
+
+    let mut iter = decoder.iter();
+    assert_eq!(iter.next(), Some(Ok(CayenneLPPScalar{ channel: 3, value: CayenneLPPValue::DigitalInput(0x55) })));
+    assert_eq!(iter.next(), Some(Err(Error::Decode(DecodeError {
+        offset: LPP_DIGITAL_INPUT_SIZE,
+        channel: 5,
+        type_code: Some(4),
+        kind: DecodeErrorKind::UnhandledType
+    }))));
+
+    assert_eq!(1, decoder.infailable_iter().count());
+}
+
+#[test]
+fn test_iter_payload_only() {
+    let mut buffer = [0u8; LPP_TEMPERATURE_SIZE + LPP_DIGITAL_INPUT_SIZE];
+    let mut lpp = CayenneLPP::new(&mut buffer);
+
+    lpp.add_temperature(5, 25.5).unwrap();
+    lpp.add_digital_input(3, 0x55).unwrap();
+
+    // after a reset the buffer still contains the old values, but only the new payload shall be decoded
+    lpp.reset();
+    lpp.add_digital_input(4, 0xAA).unwrap();
+
+    let mut iter = lpp.iter();
+    assert_eq!(iter.next(), Some(Ok(CayenneLPPScalar{ channel: 4, value: CayenneLPPValue::DigitalInput(0xAA) })));
+    assert_eq!(iter.next(), None);
+
+    // the data structure is still usable after iterating
+    lpp.add_temperature(5, 12.3).unwrap();
+    assert_eq!(2, lpp.iter().count());
+}
+
+#[test]
+fn test_add_batch() {
+    let mut buffer = [0u8; LPP_DIGITAL_INPUT_SIZE + LPP_TEMPERATURE_SIZE + LPP_GPS_SIZE];
+    let mut lpp = CayenneLPP::new(&mut buffer);
+    lpp.add_digital_input(1, 0x55).unwrap();
+
+    // the GPS value does not fit into the buffer anymore, so nothing of the batch shall be added
+    let batch = [
+        CayenneLPPScalar { channel: 2, value: CayenneLPPValue::Temperature(21.5) },
+        CayenneLPPScalar { channel: 3, value: CayenneLPPValue::DigitalInput(0xAA) },
+        CayenneLPPScalar { channel: 4, value: CayenneLPPValue::GPS(42.3519, -87.9094, 10.0) },
+    ];
+    assert_eq!(Err(Error::InsufficientMemory), lpp.add_batch(&batch));
+    assert_eq!(&[0x01, LPP_DIGITAL_INPUT, 0x55], lpp.payload_slice());
+
+    // values that are out of range also roll back the batch
+    let batch = [
+        CayenneLPPScalar { channel: 2, value: CayenneLPPValue::Temperature(21.5) },
+        CayenneLPPScalar { channel: 3, value: CayenneLPPValue::Temperature(5000.0) },
+    ];
+    assert_eq!(Err(Error::OutOfRange), lpp.add_batch(&batch));
+    assert_eq!(LPP_DIGITAL_INPUT_SIZE, lpp.payload_slice().len());
+
+    let batch = [
+        CayenneLPPScalar { channel: 2, value: CayenneLPPValue::Temperature(21.5) },
+        CayenneLPPScalar { channel: 4, value: CayenneLPPValue::GPS(42.3519, -87.9094, 10.0) },
+    ];
+    assert_eq!(Ok(Encoded::Exact), lpp.add_batch(&batch));
+    assert_eq!(3, lpp.iter().count());
+}
+
+#[test]
+fn test_checkpoint_rollback() {
+    let mut buffer = [0u8; 32];
+    let mut lpp = CayenneLPP::new(&mut buffer);
+
+    lpp.add_digital_input(1, 0x55).unwrap();
+    let checkpoint = lpp.checkpoint();
+    lpp.add_temperature(2, 21.5).unwrap();
+    lpp.add_luminosity(3, 500).unwrap();
+
+    lpp.rollback(checkpoint);
+    assert_eq!(&[0x01, LPP_DIGITAL_INPUT, 0x55], lpp.payload_slice());
+
+    // a checkpoint behind the end of the payload does not expose old bytes of the buffer
+    let checkpoint = lpp.checkpoint();
+    lpp.reset();
+    lpp.rollback(checkpoint);
+    assert!(lpp.payload_slice().is_empty());
+}
+
+struct Sensor {
+    lpp: CayenneLPPBuf<{ LPP_TEMPERATURE_SIZE + LPP_RELATIVE_HUMIDITY_SIZE }>
+}
+
+fn send(lpp: CayenneLPPBuf<{ LPP_TEMPERATURE_SIZE + LPP_RELATIVE_HUMIDITY_SIZE }>) -> usize {
+    lpp.payload_slice().len()
+}
+
+#[test]
+fn test_owned_buffer() {
+    let mut sensor = Sensor { lpp: CayenneLPPBuf::default().with_registry(REGISTRY) };
+
+    sensor.lpp.add_temperature(1, 21.5).unwrap();
+    sensor.lpp.add_relative_humidity(2, 40.0).unwrap();
+    assert_eq!(Err(Error::InsufficientMemory), sensor.lpp.add_digital_input(3, 0x55));
+    assert_eq!(&[0x01, LPP_TEMPERATURE, 0x00, 0xD7, 0x02, LPP_RELATIVE_HUMIDITY, 0x50], sensor.lpp.payload_slice());
+
+    let mut iter = sensor.lpp.iter();
+    assert_eq!(iter.next(), Some(Ok(CayenneLPPScalar { channel: 1, value: CayenneLPPValue::Temperature(21.5) })));
+    assert_eq!(iter.next(), Some(Ok(CayenneLPPScalar { channel: 2, value: CayenneLPPValue::RelativeHumidity(40.0) })));
+    assert_eq!(iter.next(), None);
+
+    // owned buffers are iterated by reference
+    assert_eq!(2, (&sensor.lpp).into_iter().count());
+    assert_eq!(2, sensor.lpp.infailable_iter().count());
+    for result in &sensor.lpp {
+        assert!(result.is_ok());
+    }
+
+    // the owned buffer can be moved to other threads
+    let lpp = std::thread::spawn(move || sensor.lpp).join().unwrap();
+    assert_eq!(LPP_TEMPERATURE_SIZE + LPP_RELATIVE_HUMIDITY_SIZE, send(lpp));
+
+    let mut lpp = CayenneLPP::from_storage([0u8; 8]);
+    lpp.add_digital_input(3, 0x55).unwrap();
+    lpp.reset();
+    lpp.add_digital_output(4, 0xAA).unwrap();
+    assert_eq!([0x04, LPP_DIGITAL_OUTPUT, 0xAA, 0, 0, 0, 0, 0], lpp.into_storage());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_vec_buffer() {
+    let mut lpp = CayenneLPPVec::default();
+
+    // the buffer grows with every value, so it never runs out of memory
+    for channel in 0..100 {
+        lpp.add_gps(channel, 42.3519, -87.9094, 10.0).unwrap();
+    }
+    assert_eq!(100 * LPP_GPS_SIZE, lpp.payload_slice().len());
+
+    lpp.reset();
+    lpp.add_temperature(1, 21.5).unwrap();
+    lpp.add_digital_input(2, 0x55).unwrap();
+    assert_eq!(&[0x01, LPP_TEMPERATURE, 0x00, 0xD7, 0x02, LPP_DIGITAL_INPUT, 0x55], lpp.payload_slice());
+    assert_eq!(2, lpp.iter().count());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_vec_buffer_only_contains_payload() {
+    let mut lpp = CayenneLPPVec::default().with_registry(REGISTRY);
+    lpp.add_temperature(1, 21.5).unwrap();
+
+    // the buffer is grown before the value of a custom type is encoded
+    assert_eq!(Err(Error::OutOfRange), lpp.add_custom_raw(2, 0xC9, 200));
+    assert_eq!(LPP_TEMPERATURE_SIZE, lpp.into_storage().len());
+
+    let mut lpp = CayenneLPPVec::default();
+    lpp.add_temperature(1, 21.5).unwrap();
+    let result = lpp.builder().gps(2, 42.3519, -87.9094, 10.0).percentage(3, 101).finish();
+    assert_eq!(Err(Error::OutOfRange), result);
+    assert_eq!(LPP_TEMPERATURE_SIZE, lpp.into_storage().len());
+
+    let mut lpp = CayenneLPPVec::default();
+    lpp.add_gps(1, 42.3519, -87.9094, 10.0).unwrap();
+    lpp.reset();
+    lpp.add_digital_input(2, 0x55).unwrap();
+    assert_eq!([0x02, LPP_DIGITAL_INPUT, 0x55], *lpp.into_storage());
+
+    #[cfg(feature = "heapless")]
+    {
+        let mut lpp = CayenneLPP::<heapless::Vec<u8, 32>>::default();
+        lpp.add_gps(1, 42.3519, -87.9094, 10.0).unwrap();
+        lpp.reset();
+        lpp.add_digital_input(2, 0x55).unwrap();
+        assert_eq!([0x02, LPP_DIGITAL_INPUT, 0x55], *lpp.into_storage());
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_decode_to_vec() {
+    let payload = [0x01, LPP_TEMPERATURE, 0x00, 0xD7, 0x02, LPP_DIGITAL_INPUT, 0x55];
+    assert_eq!(Ok(vec![
+        CayenneLPPScalar { channel: 1, value: CayenneLPPValue::Temperature(21.5) },
+        CayenneLPPScalar { channel: 2, value: CayenneLPPValue::DigitalInput(0x55) },
+    ]), decode_to_vec(&payload));
+
+    // the first error is returned instead of a partial payload
+    let payload = [0x01, LPP_TEMPERATURE, 0x00, 0xD7, 0x02, 0xC8, 0x01, 0x02];
+    assert_eq!(Err(Error::Decode(DecodeError {
+        offset: 4,
+        channel: 2,
+        type_code: Some(0xC8),
+        kind: DecodeErrorKind::UnhandledType
+    })), decode_to_vec(&payload));
+
+    // skipped extra types are no errors
+    let extra_types = [CayenneLPPExtraType { type_code: 0xC8, size: 4 }];
+    let decoder = CayenneLPPDecoder::new(&payload).with_extra_types(&extra_types);
+    assert_eq!(Ok(vec![CayenneLPPScalar { channel: 1, value: CayenneLPPValue::Temperature(21.5) }]),
+        decoder.decode_to_vec());
+}
+
+fn report<S: CayenneLPPStorage>(lpp: &mut CayenneLPP<'_, S>) -> Result<Encoded, Error> {
+    lpp.add_temperature(1, 21.5)?;
+    lpp.add_digital_input(2, 0x55)
+}
+
+#[test]
+fn test_generic_storage() {
+    let expected_bytes = [0x01, LPP_TEMPERATURE, 0x00, 0xD7, 0x02, LPP_DIGITAL_INPUT, 0x55];
+
+    let mut buffer = [0u8; 7];
+    let mut lpp = CayenneLPP::new(&mut buffer);
+    report(&mut lpp).unwrap();
+    assert_eq!(&expected_bytes, lpp.payload_slice());
+
+    let mut lpp = CayenneLPPBuf::<6>::default();
+    assert_eq!(Err(Error::InsufficientMemory), report(&mut lpp));
+
+    #[cfg(feature = "alloc")]
+    {
+        let mut lpp = CayenneLPPVec::default();
+        report(&mut lpp).unwrap();
+        assert_eq!(&expected_bytes, lpp.payload_slice());
+    }
+
+    #[cfg(feature = "heapless")]
+    {
+        let mut lpp = CayenneLPP::<heapless::Vec<u8, 7>>::default();
+        report(&mut lpp).unwrap();
+        assert_eq!(&expected_bytes, lpp.payload_slice());
+
+        // the vector only grows up to its capacity
+        let mut lpp = CayenneLPP::from_storage(heapless::Vec::<u8, 6>::new());
+        assert_eq!(Err(Error::InsufficientMemory), report(&mut lpp));
+        assert_eq!(&expected_bytes[..4], lpp.payload_slice());
+    }
+}
+
+#[test]
+fn test_builder() {
+    let mut buffer = [0u8; LPP_TEMPERATURE_SIZE + LPP_RELATIVE_HUMIDITY_SIZE + LPP_GPS_SIZE];
+    let mut lpp = CayenneLPP::new(&mut buffer);
+
+    let payload = lpp.builder()
+        .temperature(1, 21.5)
+        .relative_humidity(2, 40.0)
+        .gps(3, 42.3519, -87.9094, 10.0)
+        .finish();
+    assert_eq!(Ok((&[
+        0x01, LPP_TEMPERATURE, 0x00, 0xD7,
+        0x02, LPP_RELATIVE_HUMIDITY, 0x50,
+        0x03, LPP_GPS, 0x06, 0x76, 0x5F, 0xF2, 0x96, 0x0A, 0x00, 0x03, 0xE8
+    ][..], Encoded::Exact)), payload);
+
+    // a value that has not been written exactly is reported by finish()
+    let mut buffer = [0u8; 32];
+    let mut lpp = CayenneLPP::new(&mut buffer).with_overflow(Overflow::Clamp);
+    let result = lpp.builder()
+        .temperature(1, 21.5)
+        .barometric_pressure(2, 7000.0)
+        .digital_input(3, 0x55)
+        .finish();
+    assert_eq!(Ok(Encoded::Clamped), result.map(|(_, encoded)| encoded));
+}
+
+#[test]
+fn test_builder_error() {
+    let mut buffer = [0u8; 32];
+    let mut lpp = CayenneLPP::new(&mut buffer);
+    lpp.add_digital_input(1, 0x55).unwrap();
+
+    // the first error is returned and the values of the builder are removed
+    let result = lpp.builder()
+        .temperature(2, 21.5)
+        .temperature(3, 5000.0)
+        .custom(4, 0xC8, 1.0)
+        .digital_output(5, 0xAA)
+        .finish();
+    assert_eq!(Err(Error::OutOfRange), result);
+    assert_eq!(&[0x01, LPP_DIGITAL_INPUT, 0x55], lpp.payload_slice());
+
+    let result = lpp.builder()
+        .scalar(&CayenneLPPScalar { channel: 2, value: CayenneLPPValue::Switch(true) })
+        .custom(4, 0xC8, 1.0)
+        .finish();
+    assert_eq!(Err(Error::UnhandledType(0xC8)), result);
+
+    let mut buffer = [0u8; LPP_TEMPERATURE_SIZE];
+    let mut lpp = CayenneLPP::new(&mut buffer);
+    assert_eq!(Err(Error::InsufficientMemory), lpp.builder().temperature(1, 21.5).luminosity(2, 100).finish());
+    assert!(lpp.payload_slice().is_empty());
+}
+
+#[test]
+fn test_lpp_size_macro() {
+    const SIZE: usize = lpp_size!(Temperature, RelativeHumidity, GPS);
+    assert_eq!(LPP_TEMPERATURE_SIZE + LPP_RELATIVE_HUMIDITY_SIZE + LPP_GPS_SIZE, SIZE);
+    assert_eq!(0, lpp_size!());
+    assert_eq!(2 * LPP_DIGITAL_INPUT_SIZE, lpp_size!(DigitalInput, DigitalInput,));
+
+    let mut buffer = [0u8; lpp_size!(Temperature, Accelerometer, Switch)];
+    let mut lpp = CayenneLPP::new(&mut buffer);
+    lpp.add_temperature(1, 21.5).unwrap();
+    lpp.add_accelerometer(2, 1.0, 2.0, 3.0).unwrap();
+    lpp.add_switch(3, true).unwrap();
+    assert_eq!(Err(Error::InsufficientMemory), lpp.add_switch(4, false));
+}
+
+#[test]
+fn test_error_display() {
+    assert_eq!("the buffer is too small to add the value", Error::InsufficientMemory.to_string());
+    assert_eq!("the value is not representable by Cayenne LPP", Error::OutOfRange.to_string());
+    assert_eq!("the buffer does not contain enough bytes to unpack the next value", Error::BufferUnderrun.to_string());
+    assert_eq!("the type code 4 is invalid or not handled", Error::UnhandledType(4).to_string());
+
+    let error = Error::Decode(DecodeError {
+        offset: 6,
+        channel: 0,
+        type_code: Some(LPP_DIGITAL_INPUT),
+        kind: DecodeErrorKind::BufferUnderrun { expected: 3, available: 2 }
+    });
+    assert_eq!(
+        "failed to decode the entry at byte offset 6 (channel 0, type code 0): expected 3 bytes, but only 2 are available",
+        error.to_string()
+    );
+
+    let error = DecodeError { offset: 3, channel: 7, type_code: None, kind: DecodeErrorKind::UnhandledType };
+    assert_eq!(
+        "failed to decode the entry at byte offset 3 (channel 7, missing type code): the type code is invalid or not handled",
+        error.to_string()
+    );
+}
+
+#[test]
+fn test_error_into_boxed_error() {
+    fn add_too_much() -> Result<(), Box<dyn std::error::Error>> {
+        let mut buffer = [0u8; LPP_DIGITAL_INPUT_SIZE];
+        let mut lpp = CayenneLPP::new(&mut buffer);
+
+        lpp.add_digital_input(3, 0x55)?;
+        lpp.add_digital_input(5, 0xAA)?;
+
+        Ok(())
+    }
+
+    let error = add_too_much().unwrap_err();
+    assert_eq!(Some(&Error::InsufficientMemory), error.downcast_ref::<Error>());
+}
+
+#[test]
+fn test_decoder_lenient_mode() {
+    // 0xC8 and 0xC9 are vendor extensions with 2 and 3 bytes of data
+    const EXTRA_TYPES: [CayenneLPPExtraType; 2] = [
+        CayenneLPPExtraType { type_code: 0xC8, size: 4 },
+        CayenneLPPExtraType { type_code: 0xC9, size: 5 },
+    ];
+
+    let frame: &[u8] = &[
+        0x03, LPP_DIGITAL_INPUT, 0x55,
+        0x04, 0xC8, 0x12, 0x34,
+        0x05, LPP_TEMPERATURE, 0x00, 0xFF,
+        0x06, 0xC9, 0x12, 0x34, 0x56,
+    ];
+
+    let scalars = [
+        CayenneLPPScalar{ channel: 3, value: CayenneLPPValue::DigitalInput(0x55) },
+        CayenneLPPScalar{ channel: 5, value: CayenneLPPValue::Temperature(25.5) },
+    ];
+
+    // without the extra types, decoding stops at the first unknown type
+    let decoder = CayenneLPPDecoder::new(frame);
+    assert_eq!(1, decoder.infailable_iter().count());
+
+    // the failable iterator reports the skipped entries
+    let decoder = CayenneLPPDecoder::new(frame).with_extra_types(&EXTRA_TYPES);
+    let mut iter = decoder.iter();
+    assert_eq!(iter.next(), Some(Ok(scalars[0])));
+    assert_eq!(iter.next(), Some(Err(Error::Decode(DecodeError {
+        offset: 3,
+        channel: 4,
+        type_code: Some(0xC8),
+        kind: DecodeErrorKind::SkippedType { size: 4 }
+    }))));
+    assert_eq!(iter.next(), Some(Ok(scalars[1])));
+    assert_eq!(iter.next(), Some(Err(Error::Decode(DecodeError {
+        offset: 11,
+        channel: 6,
+        type_code: Some(0xC9),
+        kind: DecodeErrorKind::SkippedType { size: 5 }
+    }))));
+    assert_eq!(iter.next(), None);
+
+    // the infailable iterator ignores them
+    let mut count = 0;
+    for (example, result) in scalars.into_iter().zip(decoder.infailable_iter()) {
+        assert_eq!(example, result);
+        count += 1;
+    }
+    assert_eq!(count, scalars.len());
+}
+
+#[test]
+fn test_decoder_lenient_mode_underrun() {
+    const EXTRA_TYPES: [CayenneLPPExtraType; 1] = [CayenneLPPExtraType { type_code: 0xC8, size: 4 }];
+
+    let frame: &[u8] = &[0x04, 0xC8, 0x12];
+
+    let mut iter = CayenneLPPDecoder::new(frame).with_extra_types(&EXTRA_TYPES).iter();
+    assert_eq!(iter.next(), Some(Err(Error::Decode(DecodeError {
+        offset: 0,
+        channel: 4,
+        type_code: Some(0xC8),
+        kind: DecodeErrorKind::BufferUnderrun { expected: 4, available: 3 }
+    }))));
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+fn test_decoder_lenient_mode_invalid() {
+    // an entry can not be smaller than its channel and type code
+    const EXTRA_TYPES: [CayenneLPPExtraType; 2] = [
+        CayenneLPPExtraType { type_code: 0xC8, size: 4 },
+        CayenneLPPExtraType { type_code: 0xC9, size: 1 },
+    ];
+
+    let frame: &[u8] = &[0x04, 0xC9, 0x05, LPP_DIGITAL_INPUT, 0x55];
+
+    let result = CayenneLPPDecoder::new(frame).try_with_extra_types(&EXTRA_TYPES);
+    assert_eq!(Some(Error::UnhandledType(0xC9)), result.err());
+    assert!(CayenneLPPDecoder::new(frame).try_with_extra_types(&EXTRA_TYPES[..1]).is_ok());
+
+    // such extra types are ignored
+    let mut iter = CayenneLPPDecoder::new(frame).with_extra_types(&EXTRA_TYPES).iter();
+    assert_eq!(iter.next(), Some(Err(Error::Decode(DecodeError {
+        offset: 0,
+        channel: 4,
+        type_code: Some(0xC9),
+        kind: DecodeErrorKind::UnhandledType
+    }))));
+    assert_eq!(iter.next(), None);
+}
+
+// Soil moisture tension in 0.1 kPa steps, which is a vendor extension
+static SOIL_MOISTURE_TENSION: CayenneLPPScaledType =
+    CayenneLPPScaledType { type_code: 0xC8, size: 4, resolution: 0.1, signed: false };
+
+// Signed custom type with a single data byte
+static TEMPERATURE_OFFSET: CayenneLPPScaledType =
+    CayenneLPPScaledType { type_code: 0xC9, size: 3, resolution: 0.5, signed: true };
+
+static REGISTRY: CayenneLPPRegistry = CayenneLPPRegistry::new(&[&SOIL_MOISTURE_TENSION, &TEMPERATURE_OFFSET]);
+
+#[test]
+fn test_custom_types() {
+    let mut buffer = [0u8; LPP_TEMPERATURE_SIZE + 4 + 3 + 4];
+    let mut lpp = CayenneLPP::new(&mut buffer).with_registry(REGISTRY);
+
+    lpp.add_temperature(5, 25.5).unwrap();
+    lpp.add_custom(2, 0xC8, 25.0).unwrap();
+    lpp.add_custom(3, 0xC9, -1.5).unwrap();
+    lpp.add_scalar(&CayenneLPPScalar{ channel: 4, value: CayenneLPPValue::Custom(0xC8, 6553.5) }).unwrap();
+
+    let expected_bytes = [
+        0x05, LPP_TEMPERATURE, 0x00, 0xFF,
+        0x02, 0xC8, 0x00, 0xFA,
+        0x03, 0xC9, 0xFD,
+        0x04, 0xC8, 0xFF, 0xFF,
+    ];
+    assert_eq!(expected_bytes, lpp.payload_slice());
+
+    let scalars = [
+        CayenneLPPScalar{ channel: 5, value: CayenneLPPValue::Temperature(25.5) },
+        CayenneLPPScalar{ channel: 2, value: CayenneLPPValue::Custom(0xC8, 25.0) },
+        CayenneLPPScalar{ channel: 3, value: CayenneLPPValue::Custom(0xC9, -1.5) },
+        CayenneLPPScalar{ channel: 4, value: CayenneLPPValue::Custom(0xC8, 6553.5) },
+    ];
+
+    // the encoder uses its registry for iterating...
+    let mut count = 0;
+    for (example, result) in scalars.into_iter().zip(lpp.iter()) {
+        assert_eq!(Ok(example), result);
+        count += 1;
+    }
+    assert_eq!(count, scalars.len());
+
+    // ...and so does the decoder
+    let decoder = CayenneLPPDecoder::new(&expected_bytes).with_registry(REGISTRY);
+    let mut count = 0;
+    for (example, result) in scalars.into_iter().zip(decoder.infailable_iter()) {
+        assert_eq!(example, result);
+        count += 1;
+    }
+    assert_eq!(count, scalars.len());
+
+    // without the registry, the custom types are not handled
+    let decoder = CayenneLPPDecoder::new(&expected_bytes);
+    assert_eq!(1, decoder.infailable_iter().count());
+}
+
+#[test]
+fn test_custom_types_errors() {
+    let mut buffer = [0u8; 4 + 2];
+    let mut lpp = CayenneLPP::new(&mut buffer).with_registry(REGISTRY);
+
+    // not registered
+    assert_eq!(Err(Error::UnhandledType(0xCA)), lpp.add_custom(2, 0xCA, 1.0));
+
+    // not representable by the data bytes
+    assert_eq!(Err(Error::OutOfRange), lpp.add_custom(2, 0xC8, 6553.6));
+    assert_eq!(Err(Error::OutOfRange), lpp.add_custom(2, 0xC8, -1.0));
+    assert_eq!(Err(Error::OutOfRange), lpp.add_custom(2, 0xC9, 64.0));
+
+    // the failed calls must not have written anything
+    assert!(lpp.payload_slice().is_empty());
+
+    lpp.add_custom(2, 0xC8, 1.0).unwrap();
+    assert_eq!(Err(Error::InsufficientMemory), lpp.add_custom(3, 0xC8, 1.0));
+}
+
+#[test]
+fn test_custom_types_overflow() {
+    assert_eq!((0, 0xFFFF), SOIL_MOISTURE_TENSION.raw_range());
+    assert_eq!((-128, 127), TEMPERATURE_OFFSET.raw_range());
+
+    let mut buffer = [0u8; 32];
+    let mut lpp = CayenneLPP::new(&mut buffer).with_registry(REGISTRY).with_overflow(Overflow::Clamp);
+    assert_eq!(Ok(Encoded::Clamped), lpp.add_custom(1, 0xC8, 6553.6));
+    assert_eq!(Ok(Encoded::Clamped), lpp.add_custom(2, 0xC8, -1.0));
+    assert_eq!(Ok(Encoded::Clamped), lpp.add_custom(3, 0xC9, 64.0));
+    assert_eq!(Ok(Encoded::Clamped), lpp.add_custom_raw(4, 0xC9, -129));
+    assert_eq!(Ok(Encoded::Exact), lpp.add_custom(5, 0xC9, 63.5));
+    assert_eq!([
+        0x01, 0xC8, 0xFF, 0xFF,
+        0x02, 0xC8, 0x00, 0x00,
+        0x03, 0xC9, 0x7F,
+        0x04, 0xC9, 0x80,
+        0x05, 0xC9, 0x7F,
+    ], lpp.payload_slice());
+
+    // only the bits that fit into the data bytes are written
+    let mut buffer = [0u8; 32];
+    let mut lpp = CayenneLPP::new(&mut buffer).with_registry(REGISTRY).with_overflow(Overflow::Wrap);
+    assert_eq!(Ok(Encoded::Wrapped), lpp.add_custom(1, 0xC8, 6553.6));
+    assert_eq!(Ok(Encoded::Wrapped), lpp.add_custom(2, 0xC8, -0.1));
+    assert_eq!(Ok(Encoded::Wrapped), lpp.add_custom(3, 0xC9, 64.0));
+    assert_eq!(Ok(Encoded::Wrapped), lpp.add_custom_raw(4, 0xC9, -129));
+    assert_eq!([
+        0x01, 0xC8, 0x00, 0x00,
+        0x02, 0xC8, 0xFF, 0xFF,
+        0x03, 0xC9, 0x80,
+        0x04, 0xC9, 0x7F,
+    ], lpp.payload_slice());
+
+    // the default policy still rejects the values
+    let mut buffer = [0u8; 32];
+    let mut lpp = CayenneLPP::new(&mut buffer).with_registry(REGISTRY);
+    assert_eq!(Err(Error::OutOfRange), lpp.add_custom_raw(1, 0xC9, 128));
+    assert!(lpp.payload_slice().is_empty());
+}
+
+#[test]
+fn test_custom_types_invalid() {
+    // custom types can not override the data types of this library
+    static OVERRIDE: CayenneLPPScaledType =
+        CayenneLPPScaledType { type_code: LPP_TEMPERATURE, size: 4, resolution: 0.1, signed: true };
+
+    // entries contain at least the channel and the type code
+    static TOO_SMALL: CayenneLPPScaledType =
+        CayenneLPPScaledType { type_code: 0xCA, size: 1, resolution: 1.0, signed: false };
+
+    assert!(CayenneLPPRegistry::try_new(&[&SOIL_MOISTURE_TENSION, &TEMPERATURE_OFFSET]).is_ok());
+    assert_eq!(Some(Error::UnhandledType(LPP_TEMPERATURE)),
+        CayenneLPPRegistry::try_new(&[&SOIL_MOISTURE_TENSION, &OVERRIDE]).err());
+    assert_eq!(Some(Error::UnhandledType(0xCA)), CayenneLPPRegistry::try_new(&[&TOO_SMALL]).err());
+
+    // a registry that has been created statically ignores them
+    let types: [&dyn CayenneLPPCustomType; 2] = [&OVERRIDE, &TOO_SMALL];
+    let registry = CayenneLPPRegistry::new(&types);
+    assert!(registry.get(LPP_TEMPERATURE).is_none());
+    assert!(registry.get(0xCA).is_none());
+
+    let mut buffer = [0u8; 16];
+    let mut lpp = CayenneLPP::new(&mut buffer).with_registry(registry);
+    assert_eq!(Err(Error::UnhandledType(LPP_TEMPERATURE)), lpp.add_custom_raw(1, LPP_TEMPERATURE, 215));
+    assert_eq!(Err(Error::UnhandledType(LPP_TEMPERATURE)), lpp.add_custom(1, LPP_TEMPERATURE, 21.5));
+    assert_eq!(Err(Error::UnhandledType(0xCA)), lpp.add_custom_raw(1, 0xCA, 0));
+    assert!(lpp.payload_slice().is_empty());
+}
+
+#[test]
+fn test_raw_scalar_and_iter() {
+    let mut buffer = [0u8; 128];
+    let mut lpp = CayenneLPP::new(&mut buffer).with_registry(REGISTRY);
+
+    let scalars = [
+        CayenneLPPRawScalar{ channel: 3, value: CayenneLPPRawValue::DigitalInput(0x55) },
+        CayenneLPPRawScalar{ channel: 3, value: CayenneLPPRawValue::AnalogInput(1270) },
+        CayenneLPPRawScalar{ channel: 5, value: CayenneLPPRawValue::AnalogOutput(-1550) },
+        CayenneLPPRawScalar{ channel: 5, value: CayenneLPPRawValue::Temperature(-255) },
+        CayenneLPPRawScalar{ channel: 3, value: CayenneLPPRawValue::RelativeHumidity(131) },
+        CayenneLPPRawScalar{ channel: 3, value: CayenneLPPRawValue::Accelerometer(6427, 3129, -2853) },
+        CayenneLPPRawScalar{ channel: 5, value: CayenneLPPRawValue::BarometricPressure(9923) },
+        CayenneLPPRawScalar{ channel: 3, value: CayenneLPPRawValue::Voltage(12345) },
+        CayenneLPPRawScalar{ channel: 5, value: CayenneLPPRawValue::Current(12345) },
+        CayenneLPPRawScalar{ channel: 6, value: CayenneLPPRawValue::Gyrometer(1234, 5678, 900) },
+        CayenneLPPRawScalar{ channel: 1, value: CayenneLPPRawValue::GPS(423518, -879094, -1000) },
+        CayenneLPPRawScalar{ channel: 2, value: CayenneLPPRawValue::Custom(0xC8, 250) },
+        CayenneLPPRawScalar{ channel: 2, value: CayenneLPPRawValue::Custom(0xC9, -3) },
+    ];
+
+    for scalar in scalars.into_iter() {
+        lpp.add_raw_scalar(&scalar).unwrap();
+    }
+
+    let mut count = 0;
+    for (example, result) in scalars.into_iter().zip(lpp.raw_iter()) {
+        assert_eq!(Ok(example), result);
+        count += 1;
+    }
+    assert_eq!(count, scalars.len());
+
+    // the floating point iterator returns the same values in physical units
+    let mut iter = lpp.iter();
+    assert_eq!(Some(Ok(CayenneLPPValue::DigitalInput(0x55))), iter.next().map(|r| r.map(|s| s.value)));
+    assert_eq!(Some(Ok(CayenneLPPValue::AnalogInput(12.7))), iter.next().map(|r| r.map(|s| s.value)));
+    assert_eq!(Some(Ok(CayenneLPPValue::AnalogOutput(-15.5))), iter.next().map(|r| r.map(|s| s.value)));
+    assert_eq!(Some(Ok(CayenneLPPValue::Temperature(-25.5))), iter.next().map(|r| r.map(|s| s.value)));
+    assert_eq!(Some(Ok(CayenneLPPValue::RelativeHumidity(65.5))), iter.next().map(|r| r.map(|s| s.value)));
+}
+
+#[test]
+fn test_rounding_error_bounds() {
+    // Encodes the value with the given rounding policy, decodes it again and
+    // returns the error in steps of the resolution of the data type.
+    fn round_trip_error(rounding: Rounding, value: CayenneLPPValue) -> f32 {
+        let mut buffer = [0u8; LPP_GPS_SIZE];
+        let mut lpp = CayenneLPP::new(&mut buffer).with_rounding(rounding);
+        lpp.add_scalar(&CayenneLPPScalar { channel: 1, value }).unwrap();
+
+        let decoded = lpp.iter().next().unwrap().unwrap().value;
+        let errors = match (value, decoded) {
+            (CayenneLPPValue::AnalogInput(a), CayenneLPPValue::AnalogInput(b)) => [(a - b) * 100.0, 0.0, 0.0],
+            (CayenneLPPValue::Temperature(a), CayenneLPPValue::Temperature(b)) => [(a - b) * 10.0, 0.0, 0.0],
+            (CayenneLPPValue::RelativeHumidity(a), CayenneLPPValue::RelativeHumidity(b)) => [(a - b) * 2.0, 0.0, 0.0],
+            (CayenneLPPValue::BarometricPressure(a), CayenneLPPValue::BarometricPressure(b)) => [(a - b) * 10.0, 0.0, 0.0],
+            (CayenneLPPValue::Voltage(a), CayenneLPPValue::Voltage(b)) => [(a - b) * 100.0, 0.0, 0.0],
+            (CayenneLPPValue::Current(a), CayenneLPPValue::Current(b)) => [(a - b) * 1000.0, 0.0, 0.0],
+            (CayenneLPPValue::Accelerometer(x, y, z), CayenneLPPValue::Accelerometer(a, b, c)) => {
+                [(x - a) * 1000.0, (y - b) * 1000.0, (z - c) * 1000.0]
+            },
+            (CayenneLPPValue::Gyrometer(x, y, z), CayenneLPPValue::Gyrometer(a, b, c)) => {
+                [(x - a) * 100.0, (y - b) * 100.0, (z - c) * 100.0]
+            },
+            (CayenneLPPValue::GPS(x, y, z), CayenneLPPValue::GPS(a, b, c)) => {
+                [(x - a) * 10_000.0, (y - b) * 10_000.0, (z - c) * 100.0]
+            },
+            _ => panic!("unexpected value {:?}", decoded),
+        };
+
+        errors.into_iter().fold(0.0, |max: f32, error| max.max(error.abs()))
+    }
+
+    // allow a small error, because the values are not exactly representable by f32
+    const TOLERANCE: f32 = 0.01;
+
+    for i in 0..2000 {
+        let v = i as f32 * 0.0137;
+        let values = [
+            CayenneLPPValue::AnalogInput(v - 13.0),
+            CayenneLPPValue::Temperature(10.0 * v - 130.0),
+            CayenneLPPValue::RelativeHumidity(v * 4.0),
+            CayenneLPPValue::BarometricPressure(v * 100.0),
+            CayenneLPPValue::Voltage(v * 10.0),
+            CayenneLPPValue::Current(v),
+            CayenneLPPValue::Accelerometer(v - 13.0, 13.0 - v, v),
+            CayenneLPPValue::Gyrometer(v * 10.0, v, v * 5.0),
+            CayenneLPPValue::GPS(v * 3.0 - 45.0, 90.0 - v * 5.0, v * 100.0 - 1000.0),
+        ];
+
+        for value in values {
+            assert!(round_trip_error(Rounding::HalfAwayFromZero, value) <= 0.5 + TOLERANCE, "{:?}", value);
+            assert!(round_trip_error(Rounding::HalfEven, value) <= 0.5 + TOLERANCE, "{:?}", value);
+            assert!(round_trip_error(Rounding::Truncate, value) < 1.0 + TOLERANCE, "{:?}", value);
+        }
+    }
+}
+
+#[test]
+fn test_custom_types_not_finite() {
+    let mut buffer = [0u8; 4];
+    let mut lpp = CayenneLPP::new(&mut buffer).with_registry(REGISTRY);
+
+    assert_eq!(Err(Error::OutOfRange), lpp.add_custom(2, 0xC8, f32::NAN));
+    assert_eq!(Err(Error::OutOfRange), lpp.add_custom(2, 0xC8, f32::INFINITY));
+    assert!(lpp.payload_slice().is_empty());
+}
+
+#[test]
+fn test_scalar_encode_decode() {
+    let scalar = CayenneLPPScalar { channel: 3, value: CayenneLPPValue::Temperature(27.2) };
+
+    let mut buffer = [0u8; 8];
+    assert_eq!(Ok(LPP_TEMPERATURE_SIZE), scalar.encode_into(&mut buffer));
+    assert_eq!([0x03, 0x67, 0x01, 0x10], buffer[..LPP_TEMPERATURE_SIZE]);
+
+    // bytes after the scalar are not consumed
+    buffer[LPP_TEMPERATURE_SIZE..].copy_from_slice(&[0x05, 0x00, 0x55, 0x00]);
+    let (decoded, length) = CayenneLPPScalar::decode(&buffer).unwrap();
+    assert_eq!(scalar, decoded);
+    assert_eq!(LPP_TEMPERATURE_SIZE, length);
+
+    let (decoded, length) = CayenneLPPScalar::decode(&buffer[length..]).unwrap();
+    assert_eq!(CayenneLPPScalar { channel: 5, value: CayenneLPPValue::DigitalInput(0x55) }, decoded);
+    assert_eq!(LPP_DIGITAL_INPUT_SIZE, length);
+
+    let mut small_buffer = [0u8; LPP_TEMPERATURE_SIZE - 1];
+    assert_eq!(Err(Error::InsufficientMemory), scalar.encode_into(&mut small_buffer));
+
+    let custom = CayenneLPPScalar { channel: 3, value: CayenneLPPValue::Custom(0xC8, 1.0) };
+    assert_eq!(Err(Error::UnhandledType(0xC8)), custom.encode_into(&mut buffer));
+
+    assert_eq!(Err(Error::BufferUnderrun), CayenneLPPScalar::decode(&[]));
+    assert_eq!(
+        Err(Error::Decode(DecodeError { offset: 0, channel: 3, type_code: Some(0xC8), kind: DecodeErrorKind::UnhandledType })),
+        CayenneLPPScalar::decode(&[0x03, 0xC8, 0x00, 0x00])
+    );
+    assert_eq!(
+        Err(Error::Decode(DecodeError {
+            offset: 0, channel: 3, type_code: Some(LPP_TEMPERATURE), kind: DecodeErrorKind::BufferUnderrun { expected: 4, available: 3 }
+        })),
+        CayenneLPPScalar::decode(&[0x03, 0x67, 0x01])
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde() {
+    let scalars = [
+        (CayenneLPPScalar { channel: 1, value: CayenneLPPValue::Temperature(21.5) },
+            r#"{"channel":1,"value":{"temperature":21.5}}"#),
+        (CayenneLPPScalar { channel: 2, value: CayenneLPPValue::Accelerometer(1.0, -1.0, 0.5) },
+            r#"{"channel":2,"value":{"accelerometer":{"x":1.0,"y":-1.0,"z":0.5}}}"#),
+        (CayenneLPPScalar { channel: 3, value: CayenneLPPValue::GPS(42.25, -87.5, 10.0) },
+            r#"{"channel":3,"value":{"gps":{"lat":42.25,"lon":-87.5,"alt":10.0}}}"#),
+        (CayenneLPPScalar { channel: 4, value: CayenneLPPValue::Color(255, 128, 0) },
+            r#"{"channel":4,"value":{"color":{"r":255,"g":128,"b":0}}}"#),
+        (CayenneLPPScalar { channel: 5, value: CayenneLPPValue::UnixTime(1_700_000_000) },
+            r#"{"channel":5,"value":{"unixtime":1700000000}}"#),
+        (CayenneLPPScalar { channel: 6, value: CayenneLPPValue::Switch(true) },
+            r#"{"channel":6,"value":{"switch":true}}"#),
+        (CayenneLPPScalar { channel: 7, value: CayenneLPPValue::Custom(0xC8, 1.5) },
+            r#"{"channel":7,"value":{"custom":{"type_code":200,"value":1.5}}}"#),
+    ];
+
+    for (scalar, json) in scalars {
+        assert_eq!(json, serde_json::to_string(&scalar).unwrap());
+        assert_eq!(scalar, serde_json::from_str::<CayenneLPPScalar>(json).unwrap());
+
+        // formats that are not self-describing read the fields with the kind they are written with
+        let mut buffer = [0u8; 64];
+        let bytes = postcard::to_slice(&scalar, &mut buffer).unwrap();
+        assert_eq!(scalar, postcard::from_bytes::<CayenneLPPScalar>(bytes).unwrap());
+    }
+
+    for info in LPP_TYPES.iter() {
+        let value = CayenneLPPValue::from_field_values(info.lpp_type, &[1.0; 3][..info.fields.len()]).unwrap();
+        let mut buffer = [0u8; 64];
+        let bytes = postcard::to_slice(&value, &mut buffer).unwrap();
+        assert_eq!(value, postcard::from_bytes::<CayenneLPPValue>(bytes).unwrap());
+    }
+
+    // the data types are tagged with their names in LPP_TYPES
+    for info in LPP_TYPES.iter() {
+        let body = match info.lpp_type {
+            LppType::Switch => "false",
+            LppType::Accelerometer | LppType::Gyrometer => r#"{"x":1,"y":1,"z":1}"#,
+            LppType::Color => r#"{"r":1,"g":1,"b":1}"#,
+            LppType::GPS => r#"{"lat":1,"lon":1,"alt":1}"#,
+            _ => "1",
+        };
+        let json = format!(r#"{{"{}":{}}}"#, info.name, body);
+        let value: CayenneLPPValue = serde_json::from_str(&json).unwrap();
+        assert_eq!(Some(info.lpp_type), value.kind(), "{}", json);
+    }
+
+    assert!(serde_json::from_str::<CayenneLPPValue>(r#"{"unknown":1}"#).is_err());
+    assert!(serde_json::from_str::<CayenneLPPValue>(r#"{"digital_input":1.5}"#).is_err());
+    assert!(serde_json::from_str::<CayenneLPPValue>(r#"{"color":{"r":1,"g":1}}"#).is_err());
+    assert!(serde_json::from_str::<CayenneLPPValue>(r#"{"color":{"r":1,"g":1,"b":1,"a":1}}"#).is_err());
+    assert!(serde_json::from_str::<CayenneLPPValue>(r#"{"custom":{"type_code":256,"value":1}}"#).is_err());
+    assert_eq!(CayenneLPPValue::Color(3, 2, 1),
+        serde_json::from_str(r#"{"color":{"b":1,"g":2,"r":3}}"#).unwrap());
+
+    let errors = [
+        (Error::InsufficientMemory, r#""insufficient_memory""#),
+        (Error::UnhandledType(0xC8), r#"{"unhandled_type":200}"#),
+        (Error::Decode(DecodeError {
+            offset: 4,
+            channel: 2,
+            type_code: Some(LPP_TEMPERATURE),
+            kind: DecodeErrorKind::BufferUnderrun { expected: 4, available: 3 }
+        }), r#"{"decode":{"offset":4,"channel":2,"type_code":103,"kind":{"buffer_underrun":{"expected":4,"available":3}}}}"#),
+    ];
+
+    for (error, json) in errors {
+        assert_eq!(json, serde_json::to_string(&error).unwrap());
+        assert_eq!(error, serde_json::from_str::<Error>(json).unwrap());
+    }
+}
+
+#[cfg(feature = "ttn")]
+#[test]
+fn test_ttn_json() {
+    // examples of the Cayenne LPP documentation
+    let payload = [
+        0x03, 0x67, 0x01, 0x10, 0x05, 0x67, 0x00, 0xFF,
+        0x06, 0x71, 0x04, 0xD2, 0xFB, 0x2E, 0x00, 0x00,
+        0x01, 0x88, 0x06, 0x76, 0x5F, 0xF2, 0x96, 0x0A, 0x00, 0x03, 0xE8,
+        0x02, 0x00, 0x55, 0x04, 0x02, 0xFF, 0x9C,
+    ];
+    let json = r#"{"accelerometer_6":{"x":1.234,"y":-1.234,"z":0},"analog_in_4":-1,"digital_in_2":85,"gps_1":{"altitude":10,"latitude":42.3519,"longitude":-87.9094},"temperature_3":27.2,"temperature_5":25.5}"#;
+
+    let object = decode_to_ttn_json(&payload).unwrap();
+    assert_eq!(json, serde_json::to_string(&object).unwrap());
+
+    // the scalars are sorted like the keys, not like the payload
+    let mut scalars = decode_to_vec(&payload).unwrap();
+    scalars.sort_by_key(|scalar| scalar.channel);
+    let mut parsed_scalars = scalars_from_ttn_json(&object).unwrap();
+    parsed_scalars.sort_by_key(|scalar| scalar.channel);
+    assert_eq!(scalars, parsed_scalars);
+
+    // the additional data types use their names in LPP_TYPES
+    let mut lpp = CayenneLPPVec::default();
+    lpp.add_color(1, 255, 128, 0).unwrap();
+    lpp.add_switch(2, true).unwrap();
+    lpp.add_voltage(3, 3.3).unwrap();
+    let object = decode_to_ttn_json(lpp.payload_slice()).unwrap();
+    assert_eq!(r#"{"color_1":{"b":0,"g":128,"r":255},"switch_2":1,"voltage_3":3.3}"#,
+        serde_json::to_string(&object).unwrap());
+    assert_eq!(decode_to_vec(lpp.payload_slice()).unwrap(), scalars_from_ttn_json(&object).unwrap());
+
+    // custom types are not written
+    let payload = [0x01, 0xC8, 0x00, 0x01];
+    let decoder = CayenneLPPDecoder::new(&payload).with_registry(REGISTRY);
+    assert_eq!(Err(Error::UnhandledType(0xC8)), decoder.decode_to_ttn_json());
+
+    let invalid_entries = [
+        (r#"{"humidity_1":50}"#, "humidity_1", JsonErrorKind::UnknownKey),
+        (r#"{"temperature":21.5}"#, "temperature", JsonErrorKind::InvalidChannel),
+        (r#"{"temperature_x":21.5}"#, "temperature_x", JsonErrorKind::InvalidChannel),
+        (r#"{"temperature_256":21.5}"#, "temperature_256", JsonErrorKind::InvalidChannel),
+        (r#"{"temperature_1":"warm"}"#, "temperature_1", JsonErrorKind::InvalidValue),
+        (r#"{"digital_in_1":256}"#, "digital_in_1", JsonErrorKind::OutOfRange),
+        (r#"{"digital_in_1":1.5}"#, "digital_in_1", JsonErrorKind::OutOfRange),
+        (r#"{"gps_1":{"latitude":1,"longitude":1}}"#, "gps_1", JsonErrorKind::InvalidValue),
+    ];
+
+    for (json, key, kind) in invalid_entries {
+        let object = serde_json::from_str(json).unwrap();
+        assert_eq!(Err(Error::Json(JsonError { key: key.into(), kind })), scalars_from_ttn_json(&object), "{}", json);
+    }
+}
+
+#[cfg(feature = "ttn")]
+#[test]
+fn test_ttn_json_downlink() {
+    let object = serde_json::from_str(r#"{"digital_out_5": 1, "analog_out_3": 12.5}"#).unwrap();
+    assert_eq!(Ok(vec![0x03, LPP_ANALOG_OUTPUT, 0x04, 0xE2, 0x05, LPP_DIGITAL_OUTPUT, 0x01]), encode_ttn_json(&object));
+
+    // the policies of the data structure are used
+    let mut lpp = CayenneLPPVec::default().with_overflow(Overflow::Clamp);
+    let object = serde_json::from_str(r#"{"temperature_1": 5000, "color_2": {"r": 255, "g": 128, "b": 0}}"#).unwrap();
+    assert_eq!(Ok(Encoded::Clamped), lpp.add_ttn_json(&object));
+    assert_eq!(&[0x02, LPP_COLOR, 0xFF, 0x80, 0x00, 0x01, LPP_TEMPERATURE, 0x7F, 0xFF], lpp.payload_slice());
+
+    let invalid_downlinks = [
+        (r#"{"digital_out_5": 1, "relay_3": 1}"#, "relay_3", JsonErrorKind::UnknownKey),
+        (r#"{"digital_out_5": 1, "digital_out_": 1}"#, "digital_out_", JsonErrorKind::InvalidChannel),
+        (r#"{"digital_out_5": 1, "digital_out_-1": 1}"#, "digital_out_-1", JsonErrorKind::InvalidChannel),
+        (r#"{"digital_out_5": 1, "digital_out_5a": 1}"#, "digital_out_5a", JsonErrorKind::InvalidChannel),
+        (r#"{"digital_out_5": 1, "digital_out_6": true}"#, "digital_out_6", JsonErrorKind::InvalidValue),
+        (r#"{"digital_out_5": 1, "digital_out_6": -1}"#, "digital_out_6", JsonErrorKind::OutOfRange),
+        (r#"{"digital_out_5": 1, "analog_out_3": 400}"#, "analog_out_3", JsonErrorKind::OutOfRange),
+        (r#"{"digital_out_5": 1, "percentage_3": 101}"#, "percentage_3", JsonErrorKind::OutOfRange),
+    ];
+
+    for (json, key, kind) in invalid_downlinks {
+        let object = serde_json::from_str(json).unwrap();
+        assert_eq!(Err(Error::Json(JsonError { key: key.into(), kind })), encode_ttn_json(&object), "{}", json);
+
+        // the payload stays unchanged
+        let mut lpp = CayenneLPPVec::default();
+        lpp.add_digital_input(1, 0x55).unwrap();
+        assert!(lpp.add_ttn_json(&object).is_err());
+        assert_eq!(&[0x01, LPP_DIGITAL_INPUT, 0x55], lpp.payload_slice());
+    }
+
+    let mut buffer = [0u8; 4];
+    let mut lpp = CayenneLPP::new(&mut buffer);
+    let object = serde_json::from_str(r#"{"digital_out_5": 1, "digital_out_6": 0}"#).unwrap();
+    assert_eq!(Err(Error::InsufficientMemory), lpp.add_ttn_json(&object));
+    assert!(lpp.payload_slice().is_empty());
+}
+
+#[derive(Default)]
+struct Led {
+    color: (u8, u8, u8),
+    commands: usize,
+}
+
+impl CayenneLPPHandler for Led {
+    fn handle(&mut self, channel: u8, command: CayenneLPPCommand) {
+        assert_eq!(2, channel);
+        if let CayenneLPPCommand::Color(r, g, b) = command {
+            self.color = (r, g, b);
+        }
+        self.commands += 1;
+    }
+}
+
+#[test]
+fn test_dispatcher() {
+    let mut led = Led::default();
+    let mut outputs = [0u8; 2];
+    let mut analog_output = 0.0;
+    let mut set_output = |channel: u8, command: CayenneLPPCommand| {
+        if let CayenneLPPCommand::DigitalOutput(value) = command {
+            outputs[channel as usize] = value;
+        }
+    };
+    let mut set_analog_output = |_channel: u8, command: CayenneLPPCommand| {
+        if let CayenneLPPCommand::AnalogOutput(value) = command {
+            analog_output = value;
+        }
+    };
+
+    let mut routes = [
+        CayenneLPPRoute { channel: 1, lpp_type: LppType::DigitalOutput, handler: &mut set_output },
+        CayenneLPPRoute { channel: 2, lpp_type: LppType::Color, handler: &mut led },
+        CayenneLPPRoute { channel: 3, lpp_type: LppType::AnalogOutput, handler: &mut set_analog_output },
+    ];
+    let mut dispatcher = CayenneLPPDispatcher::new(&mut routes);
+
+    let mut downlink = [0u8; 64];
+    let mut lpp = CayenneLPP::new(&mut downlink);
+    lpp.add_digital_output(1, 0x55).unwrap();
+    lpp.add_color(2, 255, 128, 0).unwrap();
+    lpp.add_analog_output(3, 12.5).unwrap();
+    // the switch is not routed, the temperature is no command
+    lpp.add_switch(4, true).unwrap();
+    lpp.add_temperature(5, 21.5).unwrap();
+    // the digital output is routed to another channel
+    lpp.add_digital_output(2, 0x01).unwrap();
+
+    let mut errors = Vec::new();
+    assert_eq!(3, dispatcher.dispatch(lpp.payload_slice(), |error| errors.push(error)));
+    assert_eq!(vec![
+        DispatchError::UnknownChannel { channel: 4, type_code: LPP_SWITCH },
+        DispatchError::InputOnlyType { channel: 5, type_code: LPP_TEMPERATURE },
+        DispatchError::UnknownChannel { channel: 2, type_code: LPP_DIGITAL_OUTPUT },
+    ], errors);
+
+    // decode errors are reported and the dispatching continues if possible
+    let downlink = [
+        0x02, LPP_GPS, 0x7F, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x02, LPP_COLOR, 0x01, 0x02, 0x03,
+        0x01, 0xC8, 0x00
+    ];
+    let mut errors = Vec::new();
+    assert_eq!(1, dispatcher.dispatch(&downlink, |error| errors.push(error)));
+    assert_eq!(vec![
+        DispatchError::Decode(DecodeError { offset: 0, channel: 2, type_code: Some(LPP_GPS), kind: DecodeErrorKind::OutOfRange }),
+        DispatchError::Decode(DecodeError { offset: 16, channel: 1, type_code: Some(0xC8), kind: DecodeErrorKind::UnhandledType }),
+    ], errors);
+
+    assert_eq!([0, 0x55], outputs);
+    assert_eq!(12.5, analog_output);
+    assert_eq!((1, 2, 3), led.color);
+    assert_eq!(2, led.commands);
+}
+
+#[test]
+fn test_field_values() {
+    let value = CayenneLPPValue::GPS(42.3519, -87.9094, 10.0);
+    assert_eq!(Some(42.3519f32 as f64), value.field_value(0));
+    assert_eq!(Some(10.0), value.field_value(2));
+    assert_eq!(None, value.field_value(3));
+    assert_eq!(Some(1_700_000_001.0), CayenneLPPValue::UnixTime(1_700_000_001).field_value(0));
+    assert_eq!(Some(1.0), CayenneLPPValue::Switch(true).field_value(0));
+
+    assert_eq!(Some(value), CayenneLPPValue::from_field_values(LppType::GPS, &[42.3519, -87.9094, 10.0]));
+    assert_eq!(Some(CayenneLPPValue::Color(255, 128, 0)),
+        CayenneLPPValue::from_field_values(LppType::Color, &[255.0, 128.0, 0.0]));
+    assert_eq!(Some(CayenneLPPValue::Switch(false)), CayenneLPPValue::from_field_values(LppType::Switch, &[0.0]));
+
+    // the number of values and their types have to match the fields
+    assert_eq!(None, CayenneLPPValue::from_field_values(LppType::GPS, &[42.3519, -87.9094]));
+    assert_eq!(None, CayenneLPPValue::from_field_values(LppType::Temperature, &[21.5, 1.0]));
+    assert_eq!(None, CayenneLPPValue::from_field_values(LppType::Color, &[256.0, 0.0, 0.0]));
+    assert_eq!(None, CayenneLPPValue::from_field_values(LppType::DigitalOutput, &[1.5]));
+    assert_eq!(None, CayenneLPPValue::from_field_values(LppType::Switch, &[2.0]));
+
+    // every data type can be created from the values of its fields
+    for lpp_type in LppType::ALL {
+        let values: Vec<f64> = lpp_type.info().fields.iter().map(|field| field.min() as f64).collect();
+        let value = CayenneLPPValue::from_field_values(lpp_type, &values).unwrap();
+        assert_eq!(Some(lpp_type), value.kind());
+        assert_eq!(values, (0..values.len()).map(|index| value.field_value(index).unwrap()).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,103 @@
+#![cfg(feature = "cli")]
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn run(args: &[&str], stdin: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_cayenne-lpp"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child.stdin.take().unwrap().write_all(stdin).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn test_cli_decode() {
+    let expected_table = "\
+channel  type           value                                               unit
+      3  temperature    27.2                                                °C
+      6  accelerometer  x=1.234 y=-1.234 z=0.000                            G
+      1  gps            latitude=42.3519 longitude=-87.9094 altitude=10.00  °, °, m
+      2  digital_input  85
+";
+
+    let payload = "03 67 01 10 06 71 04 D2 FB 2E 00 00 01 88 06 76 5F F2 96 0A 00 03 E8 02 00 55";
+    let output = run(&["decode", payload], b"");
+    assert!(output.status.success());
+    assert_eq!(expected_table, stdout(&output));
+
+    // the payload is read from stdin in hex, base64 or as raw bytes
+    let bytes = [0x03, 0x67, 0x01, 0x10];
+    let expected_json: serde_json::Value = serde_json::from_str(
+        r#"[{"channel": 3, "type": "temperature", "value": 27.2, "unit": "°C"}]"#).unwrap();
+    for (args, stdin) in [
+        (&["decode", "--json"][..], &b"0x03670110\n"[..]),
+        (&["decode", "--json", "--base64"][..], &b"A2cBEA==\n"[..]),
+        (&["decode", "--json", "--raw"][..], &bytes[..]),
+    ] {
+        let output = run(args, stdin);
+        assert!(output.status.success(), "{:?}", args);
+        assert_eq!(expected_json, serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap(), "{:?}", args);
+    }
+
+    let output = run(&["decode", "--json", "06 71 04 D2 FB 2E 00 00 01 88 06 76 5F F2 96 0A 00 03 E8"], b"");
+    let expected_json: serde_json::Value = serde_json::from_str(r#"[
+        {"channel": 6, "type": "accelerometer", "value": {"x": 1.234, "y": -1.234, "z": 0.0}, "unit": "G"},
+        {"channel": 1, "type": "gps", "value": {"latitude": 42.3519, "longitude": -87.9094, "altitude": 10.0}, "unit": "°, °, m"}
+    ]"#).unwrap();
+    assert_eq!(expected_json, serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap());
+
+    // the values are written with every step of their resolution, e.g., 0.5 % for a relative humidity
+    let output = run(&["decode", "036851"], b"");
+    assert_eq!("\
+channel  type               value  unit
+      3  relative_humidity  40.5   %
+", stdout(&output));
+    let output = run(&["decode", "--json", "036851"], b"");
+    let expected_json: serde_json::Value = serde_json::from_str(
+        r#"[{"channel": 3, "type": "relative_humidity", "value": 40.5, "unit": "%"}]"#).unwrap();
+    assert_eq!(expected_json, serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap());
+
+    // payloads that are valid in hex and base64 are decoded as hex unless --base64 is given
+    assert!(!run(&["decode", "BABF"], b"").status.success());
+    let output = run(&["decode", "--json", "--base64", "BABF"], b"");
+    let expected_json: serde_json::Value = serde_json::from_str(
+        r#"[{"channel": 4, "type": "digital_input", "value": 69, "unit": ""}]"#).unwrap();
+    assert_eq!(expected_json, serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap());
+
+    // valid entries are printed, errors are reported
+    let output = run(&["decode", "03 67 01 10 02 C8 00"], b"");
+    assert!(!output.status.success());
+    assert!(stdout(&output).contains("temperature"));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("type code 200"));
+
+    let output = run(&["decode", "not a payload!"], b"");
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn test_cli_encode() {
+    let output = run(&["encode", "temperature:5=21.5", "gps:1=42.3519,-87.9094,10", "switch:2=1"], b"");
+    assert!(output.status.success());
+    assert_eq!("056700D7018806765FF2960A0003E8028E01\n", stdout(&output));
+
+    let output = run(&["encode", "--base64", "temperature:3=27.2"], b"");
+    assert_eq!("A2cBEA==\n", stdout(&output));
+
+    for arg in ["temperature:5=5000", "humidity:1=50", "temperature:256=1", "temperature:x=1", "temperature:1",
+        "gps:1=1,2", "digital_output:1=1.5", "temperature:1=warm"] {
+        let output = run(&["encode", arg], b"");
+        assert!(!output.status.success(), "{}", arg);
+        assert!(String::from_utf8_lossy(&output.stderr).contains(arg), "{}", arg);
+    }
+}